@@ -1,11 +1,18 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_spl::token::Mint;
 
+use crate::state::{
+    CollateralPricingMode, CrossMarginBalance, GovernanceAction, LiquidationPriceMode, OracleFeed,
+    ProtocolRole, SettlementPhase, MAX_CROSS_MARGIN_COLLATERALS, MAX_ORACLES_PER_COLLATERAL, PAUSE_MINT,
+    REDEMPTION_BUCKETS,
+};
+
 /// Program-wide configuration and parameters.
-/// PDA seed: ["protocol", authority]
+/// PDA seed: ["protocol", seed_key]
 #[account]
 pub struct Protocol {
-    /// Governance / admin authority (e.g., multisig)
+    /// Root admin (e.g., multisig): manages roles and protocol setup
     pub authority: Pubkey,
 
     /// Bump for the Protocol PDA
@@ -17,11 +24,45 @@ pub struct Protocol {
     /// Global debt ceiling in PUSD (6 decimals typical)
     pub global_debt_ceiling: u64,
 
-    /// Whether minting is paused (emergency circuit breaker)
-    pub mint_paused: bool,
+    /// Outstanding PUSD debt across all positions
+    pub total_debt_pusd: u64,
+
+    /// Fees booked into debt (stability fees, liquidation penalties) not yet minted to the treasury
+    pub accrued_surplus_pusd: u64,
+
+    /// Cumulative fees minted into the treasury
+    pub realized_fees_pusd: u64,
+
+    /// Debt left on positions whose collateral was exhausted; PUSD no position will repay
+    pub bad_debt_pusd: u64,
+
+    /// Protocol-wide pause switches (PAUSE_* bitmask; emergency circuit breaker)
+    pub paused_actions: u8,
+
+    /// Governance PDA once the timelock is enabled (default: none, risk parameters are fixed)
+    pub governance: Pubkey,
+
+    /// Guardian: may pause, without timelock
+    pub guardian: Pubkey,
+
+    /// Risk admin: lists collateral and changes risk parameters (through the timelock once enabled)
+    pub risk_admin: Pubkey,
+
+    /// Treasury role: sweeps surplus and covers bad debt
+    pub treasury_authority: Pubkey,
+
+    /// Proposed root admin awaiting `accept_authority` (default: none)
+    pub pending_authority: Pubkey,
+
+    /// Key the PDA was derived from: the PUSD mint, or the original authority for
+    /// deployments migrated from the authority-seeded layout. Never changes.
+    pub seed_key: Pubkey,
+
+    /// Global settlement phase (Live until `cage`)
+    pub settlement_phase: SettlementPhase,
 
     /// Reserved for future upgrades (alignment/padding)
-    pub _reserved: [u8; 7],
+    pub _reserved: [u8; 6],
 }
 impl Protocol {
     pub const SEED_PREFIX: &'static [u8] = b"protocol";
@@ -30,8 +71,87 @@ impl Protocol {
         + 1   // bump
         + 32  // pusd_mint
         + 8   // global_debt_ceiling
-        + 1   // mint_paused
-        + 7;  // _reserved
+        + 8   // total_debt_pusd
+        + 8   // accrued_surplus_pusd
+        + 8   // realized_fees_pusd
+        + 8   // bad_debt_pusd
+        + 1   // paused_actions
+        + 32  // governance
+        + 32  // guardian
+        + 32  // risk_admin
+        + 32  // treasury_authority
+        + 32  // pending_authority
+        + 32  // seed_key
+        + 1   // settlement_phase
+        + 6;  // _reserved
+
+    /// Size of the original authority-seeded layout: discriminator, authority, bump,
+    /// pusd_mint, global_debt_ceiling, mint_paused, 7 reserved bytes
+    pub const BASELINE_LEN: usize = 8 + 32 + 1 + 32 + 8 + 1 + 7;
+
+    /// Decode an account written with the original layout into the current one.
+    /// Every field that layout lacked is set explicitly; `mint_paused` carries over as
+    /// PAUSE_MINT. Returns None unless `data` is a baseline-sized Protocol.
+    pub fn from_baseline(data: &[u8]) -> Option<Protocol> {
+        if data.len() != Self::BASELINE_LEN || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        let authority = Pubkey::try_from(&data[8..40]).ok()?;
+        let bump = data[40];
+        let pusd_mint = Pubkey::try_from(&data[41..73]).ok()?;
+        let global_debt_ceiling = u64::from_le_bytes(data[73..81].try_into().ok()?);
+        let mint_paused = data[81] != 0;
+
+        Some(Protocol {
+            authority,
+            bump,
+            pusd_mint,
+            global_debt_ceiling,
+            total_debt_pusd: 0,
+            accrued_surplus_pusd: 0,
+            realized_fees_pusd: 0,
+            bad_debt_pusd: 0,
+            paused_actions: if mint_paused { PAUSE_MINT } else { 0 },
+            governance: Pubkey::default(),
+            guardian: authority,
+            risk_admin: authority,
+            treasury_authority: authority,
+            pending_authority: Pubkey::default(),
+            seed_key: authority,
+            settlement_phase: SettlementPhase::Live,
+            _reserved: [0; 6],
+        })
+    }
+
+    /// Promote the pending nominee to root admin; returns the previous admin,
+    /// or None when nobody is nominated
+    pub fn accept_pending_authority(&mut self) -> Option<Pubkey> {
+        if self.pending_authority == Pubkey::default() {
+            return None;
+        }
+        let old_authority = self.authority;
+        self.authority = std::mem::take(&mut self.pending_authority);
+        Some(old_authority)
+    }
+
+    /// Key currently holding a delegated role
+    pub fn role_holder(&self, role: ProtocolRole) -> Pubkey {
+        match role {
+            ProtocolRole::Guardian => self.guardian,
+            ProtocolRole::RiskAdmin => self.risk_admin,
+            ProtocolRole::Treasury => self.treasury_authority,
+        }
+    }
+
+    /// Hand a delegated role to `new_holder`; returns the previous holder
+    pub fn set_role_holder(&mut self, role: ProtocolRole, new_holder: Pubkey) -> Pubkey {
+        let slot = match role {
+            ProtocolRole::Guardian => &mut self.guardian,
+            ProtocolRole::RiskAdmin => &mut self.risk_admin,
+            ProtocolRole::Treasury => &mut self.treasury_authority,
+        };
+        std::mem::replace(slot, new_holder)
+    }
 }
 
 /// Supported collateral configuration (one per asset).
@@ -47,6 +167,36 @@ pub struct CollateralConfig {
     /// Vault token account (owned by PDA) where collateral is held
     pub vault: Pubkey,
 
+    /// Oracle sources used to value this collateral (first `oracle_count` are set)
+    pub oracles: [OracleFeed; MAX_ORACLES_PER_COLLATERAL],
+
+    /// Number of registered oracle sources (1..=MAX_ORACLES_PER_COLLATERAL)
+    pub oracle_count: u8,
+
+    /// Minimum number of fresh sources required to price this collateral
+    pub min_fresh_oracles: u8,
+
+    /// Maximum spread between fresh sources, relative to the median (bps)
+    pub max_oracle_deviation_bps: u16,
+
+    /// Confidence multiplier k (bps, 10_000 = 1.0x conf) applied to the price band
+    pub conf_multiplier_bps: u16,
+
+    /// Maximum conf/price ratio (bps) before the price is rejected outright
+    pub max_conf_ratio_bps: u16,
+
+    /// Price used for liquidation eligibility (upper band or midpoint)
+    pub liquidation_price_mode: LiquidationPriceMode,
+
+    /// Whether the oracles price the token directly or SOL scaled by a stake pool rate
+    pub pricing_mode: CollateralPricingMode,
+
+    /// Stake pool whose SOL-per-token rate prices this collateral (StakePool mode only)
+    pub stake_pool: Pubkey,
+
+    /// Maximum move of the stake pool rate versus its previous epoch (bps)
+    pub max_stake_rate_change_bps: u16,
+
     /// Initial LTV (bps). e.g., 6600 = 66.00%
     pub initial_ltv_bps: u16,
 
@@ -56,17 +206,91 @@ pub struct CollateralConfig {
     /// Liquidation bonus (bps). e.g., 500 = 5.00%
     pub liq_bonus_bps: u16,
 
+    /// Liquidation penalty (bps) charged on liquidated debt and booked as protocol surplus
+    pub liq_penalty_bps: u16,
+
+    /// Close factor (bps): max fraction of a position's debt repayable per liquidation
+    pub close_factor_bps: u16,
+
+    /// Health (bps) a partial liquidation may restore a position to; 0 disables the cap
+    pub target_health_bps: u16,
+
+    /// Positions with debt at or below this (or that would be left with it) can be fully liquidated
+    pub dust_threshold_pusd: u64,
+
+    /// Auction start price as a fraction of the oracle price (bps), e.g. 12_000 = 120%
+    pub auction_start_premium_bps: u16,
+
+    /// Auction floor price as a fraction of the oracle price (bps), e.g. 7_000 = 70%
+    pub auction_floor_bps: u16,
+
+    /// Seconds for the auction price to decay linearly from start to floor
+    pub auction_duration_secs: u32,
+
     /// Per-collateral debt ceiling in PUSD (limits concentration)
     pub debt_ceiling: u64,
 
+    /// Outstanding PUSD debt backed by this collateral
+    pub total_debt_pusd: u64,
+
+    /// Collateral held across all positions of this type (smallest units)
+    pub total_collateral: u64,
+
+    /// Annualized stability fee (bps) charged on debt backed by this collateral
+    pub stability_fee_bps: u16,
+
+    /// Cumulative stability fee index (RATE_INDEX_ONE = 1.0); debt = normalized * index
+    pub rate_index: u128,
+
+    /// Last time (Unix ts) `rate_index` was accrued
+    pub last_accrual_ts: i64,
+
+    /// Sum of all positions' normalized debt for this collateral
+    pub total_normalized_debt: u128,
+
+    /// Minimum redemption fee (bps), before the base rate is added
+    pub redemption_fee_floor_bps: u16,
+
+    /// Redemption base rate (RATE_INDEX_ONE = 100%); jumps on redemptions, decays over time
+    pub redemption_base_rate: u128,
+
+    /// Last time (Unix ts) the redemption base rate was updated
+    pub last_redemption_ts: i64,
+
+    /// Redemption index: number of indebted positions per health bucket (riskiest first)
+    pub redemption_buckets: [u32; REDEMPTION_BUCKETS],
+
+    /// Settlement price frozen by `cage_collateral` (USD 6dp per whole token; 0 = not frozen)
+    pub final_price_6dp: u64,
+
+    /// Collateral set aside for PUSD holders by settled positions
+    pub settlement_collateral: u64,
+
+    /// Debt settled positions could not cover at the final price
+    pub settlement_shortfall_pusd: u64,
+
+    /// Last change to a step-capped risk parameter (Unix ts); see `risk_update_due`
+    pub last_risk_update_ts: i64,
+
     /// Active flag (false disables new mints for this collateral)
     pub active: bool,
 
+    /// Per-collateral pause switches (PAUSE_* bitmask, on top of the protocol-wide ones)
+    pub paused_actions: u8,
+
     /// Bump for the CollateralConfig PDA
     pub bump: u8,
 
+    /// Cross-margin balances of this collateral not yet settled; `cash_collateral` waits for
+    /// them, since settling them adds to `settlement_collateral`
+    pub cross_margin_balances: u32,
+
+    /// Auctions of this collateral still running; `cash_collateral` waits for them, since
+    /// settling them adds to `settlement_collateral`
+    pub active_auctions: u32,
+
     /// Reserved
-    pub _reserved: [u8; 6],
+    pub _reserved: [u8; 2],
 }
 impl CollateralConfig {
     pub const SEED_PREFIX: &'static [u8] = b"collateral";
@@ -74,13 +298,66 @@ impl CollateralConfig {
         + 32  // protocol
         + 32  // collateral_mint
         + 32  // vault
+        + OracleFeed::LEN * MAX_ORACLES_PER_COLLATERAL // oracles
+        + 1   // oracle_count
+        + 1   // min_fresh_oracles
+        + 2   // max_oracle_deviation_bps
+        + 2   // conf_multiplier_bps
+        + 2   // max_conf_ratio_bps
+        + 1   // liquidation_price_mode
+        + 1   // pricing_mode
+        + 32  // stake_pool
+        + 2   // max_stake_rate_change_bps
         + 2   // initial_ltv_bps
         + 2   // maintenance_ltv_bps
         + 2   // liq_bonus_bps
+        + 2   // liq_penalty_bps
+        + 2   // close_factor_bps
+        + 2   // target_health_bps
+        + 8   // dust_threshold_pusd
+        + 2   // auction_start_premium_bps
+        + 2   // auction_floor_bps
+        + 4   // auction_duration_secs
         + 8   // debt_ceiling
+        + 8   // total_debt_pusd
+        + 8   // total_collateral
+        + 2   // stability_fee_bps
+        + 16  // rate_index
+        + 8   // last_accrual_ts
+        + 16  // total_normalized_debt
+        + 2   // redemption_fee_floor_bps
+        + 16  // redemption_base_rate
+        + 8   // last_redemption_ts
+        + 4 * REDEMPTION_BUCKETS // redemption_buckets
+        + 8   // final_price_6dp
+        + 8   // settlement_collateral
+        + 8   // settlement_shortfall_pusd
+        + 8   // last_risk_update_ts
         + 1   // active
+        + 1   // paused_actions
         + 1   // bump
-        + 6;  // _reserved
+        + 4   // cross_margin_balances
+        + 4   // active_auctions
+        + 2;  // _reserved
+
+    /// Price accounts expected ahead of any other remaining accounts: the oracles, then the
+    /// stake pool in StakePool mode.
+    pub fn price_account_count(&self) -> usize {
+        match self.pricing_mode {
+            CollateralPricingMode::Direct => self.oracle_count as usize,
+            CollateralPricingMode::StakePool => self.oracle_count as usize + 1,
+        }
+    }
+
+    /// Whether holders may cash packed PUSD against this collateral: the price is frozen and
+    /// nothing left unsettled (positions, cross-margin balances, auctions) can still add to
+    /// `settlement_collateral`.
+    pub fn settlement_cashable(&self) -> bool {
+        self.final_price_6dp > 0
+            && self.total_normalized_debt == 0
+            && self.cross_margin_balances == 0
+            && self.active_auctions == 0
+    }
 }
 
 /// User position per collateral mint (simple 1:1 model).
@@ -96,12 +373,18 @@ pub struct Position {
     /// Amount of collateral deposited (in smallest units)
     pub collateral_amount: u64,
 
-    /// PUSD debt (principal). Fees can be handled off-chain and realized on actions.
+    /// PUSD debt including stability fees, as of `last_accrual_ts`
     pub debt_pusd: u64,
 
-    /// Last time (Unix ts) interest/fees were realized (optional use)
+    /// Debt divided by the collateral's rate index at borrow time; grows into `debt_pusd`
+    pub normalized_debt: u128,
+
+    /// Last time (Unix ts) stability fees were realized into `debt_pusd`
     pub last_accrual_ts: i64,
 
+    /// Redemption index bucket this position is counted in (REDEMPTION_BUCKET_NONE if debt-free)
+    pub redemption_bucket: u8,
+
     /// Bump for the Position PDA
     pub bump: u8,
 
@@ -115,21 +398,377 @@ impl Position {
         + 32  // collateral_config
         + 8   // collateral_amount
         + 8   // debt_pusd
+        + 16  // normalized_debt
         + 8   // last_accrual_ts
+        + 1   // redemption_bucket
         + 1   // bump
         + 7;  // _reserved
 }
 
+/// Cross-margined position: balances of several collateral types backing one PUSD debt.
+/// The debt accrues at, and counts against the ceiling of, `debt_collateral_config`.
+/// Collateral sits in each collateral's vault alongside isolated positions.
+/// PDA seed: ["cross_margin", owner]
+#[account]
+pub struct CrossMarginAccount {
+    /// Account owner
+    pub owner: Pubkey,
+
+    /// Protocol every balance's collateral belongs to
+    pub protocol: Pubkey,
+
+    /// Collateral config whose rate index, fees and debt ceiling the debt is booked under
+    pub debt_collateral_config: Pubkey,
+
+    /// Collateral balances (first `balance_count` are set)
+    pub balances: [CrossMarginBalance; MAX_CROSS_MARGIN_COLLATERALS],
+
+    /// Number of collateral types held (0..=MAX_CROSS_MARGIN_COLLATERALS)
+    pub balance_count: u8,
+
+    /// PUSD debt including stability fees, as of `last_accrual_ts`
+    pub debt_pusd: u64,
+
+    /// Debt divided by the debt collateral's rate index at borrow time
+    pub normalized_debt: u128,
+
+    /// Last time (Unix ts) stability fees were realized into `debt_pusd`
+    pub last_accrual_ts: i64,
+
+    /// Bump for the CrossMarginAccount PDA
+    pub bump: u8,
+
+    /// Set by `settle_cross_margin`; balances no longer count against `cash_collateral`
+    pub settled: bool,
+
+    /// Reserved
+    pub _reserved: [u8; 5],
+}
+impl CrossMarginAccount {
+    pub const SEED_PREFIX: &'static [u8] = b"cross_margin";
+    pub const LEN: usize = 8
+        + 32  // owner
+        + 32  // protocol
+        + 32  // debt_collateral_config
+        + CrossMarginBalance::LEN * MAX_CROSS_MARGIN_COLLATERALS // balances
+        + 1   // balance_count
+        + 8   // debt_pusd
+        + 16  // normalized_debt
+        + 8   // last_accrual_ts
+        + 1   // bump
+        + 1   // settled
+        + 5;  // _reserved
+
+    /// Slot holding `collateral_config`, if the account has a balance of it.
+    pub fn balance_index(&self, collateral_config: &Pubkey) -> Option<usize> {
+        self.balances[..self.balance_count as usize]
+            .iter()
+            .position(|balance| balance.collateral_config == *collateral_config)
+    }
+
+    /// Drop an emptied balance, keeping the remaining ones in order.
+    pub fn remove_balance(&mut self, index: usize) {
+        let count = self.balance_count as usize;
+        self.balances.copy_within(index + 1..count, index);
+        self.balances[count - 1] = CrossMarginBalance::default();
+        self.balance_count -= 1;
+    }
+}
+
+/// Dutch auction selling a liquidated position's collateral for PUSD.
+/// Collateral stays in the collateral vault, earmarked for the auction.
+/// PDA seed: ["auction", position]
+#[account]
+pub struct Auction {
+    /// Collateral config of the auctioned position
+    pub collateral_config: Pubkey,
+
+    /// Position being liquidated
+    pub position: Pubkey,
+
+    /// Position owner (receives leftover collateral)
+    pub owner: Pubkey,
+
+    /// Collateral still for sale (smallest units)
+    pub collateral_amount: u64,
+
+    /// PUSD still to be raised (debt plus liquidation penalty)
+    pub debt_remaining_pusd: u64,
+
+    /// Price (USD 6dp per whole token) at `start_ts`
+    pub start_price_6dp: u64,
+
+    /// Price the auction decays to and then holds
+    pub floor_price_6dp: u64,
+
+    /// Auction start time (Unix ts)
+    pub start_ts: i64,
+
+    /// Seconds to decay from start to floor
+    pub duration_secs: u32,
+
+    /// Whether the auction is running
+    pub active: bool,
+
+    /// Bump for the Auction PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 6],
+}
+impl Auction {
+    pub const SEED_PREFIX: &'static [u8] = b"auction";
+    pub const LEN: usize = 8
+        + 32 // collateral_config
+        + 32 // position
+        + 32 // owner
+        + 8  // collateral_amount
+        + 8  // debt_remaining_pusd
+        + 8  // start_price_6dp
+        + 8  // floor_price_6dp
+        + 8  // start_ts
+        + 4  // duration_secs
+        + 1  // active
+        + 1  // bump
+        + 6; // _reserved
+}
+
+/// Stability pool for one collateral type: PUSD deposits absorb liquidated debt in exchange
+/// for the seized collateral. Gained collateral stays in the collateral vault until claimed.
+/// PDA seed: ["stability_pool", collateral_config]
+#[account]
+pub struct StabilityPool {
+    /// Collateral config whose liquidations this pool absorbs
+    pub collateral_config: Pubkey,
+
+    /// PUSD token account (owned by this PDA) holding deposits
+    pub pusd_account: Pubkey,
+
+    /// Total compounded PUSD deposits
+    pub total_deposits: u64,
+
+    /// Collateral earned by depositors and not yet claimed (held in the vault)
+    pub collateral_gains: u64,
+
+    /// Running product P (SP_PRECISION = 1.0)
+    pub product_p: u128,
+
+    /// Running sum S for the current scale
+    pub sum_s: u128,
+
+    /// Final sum S of the previous scale
+    pub sum_s_previous: u128,
+
+    /// Number of times P has been rescaled
+    pub scale: u64,
+
+    /// Bump for the StabilityPool PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl StabilityPool {
+    pub const SEED_PREFIX: &'static [u8] = b"stability_pool";
+    pub const PUSD_ACCOUNT_SEED_PREFIX: &'static [u8] = b"stability_pool_pusd";
+    pub const LEN: usize = 8
+        + 32 // collateral_config
+        + 32 // pusd_account
+        + 8  // total_deposits
+        + 8  // collateral_gains
+        + 16 // product_p
+        + 16 // sum_s
+        + 16 // sum_s_previous
+        + 8  // scale
+        + 1  // bump
+        + 7; // _reserved
+}
+
+/// A depositor's stake in a stability pool, with accumulator snapshots from their last touch.
+/// PDA seed: ["stability_deposit", stability_pool, depositor]
+#[account]
+pub struct StabilityDeposit {
+    /// Pool this deposit belongs to
+    pub stability_pool: Pubkey,
+
+    /// Depositor
+    pub depositor: Pubkey,
+
+    /// Deposit as of the snapshot (compounds down as the pool absorbs debt)
+    pub initial_deposit: u64,
+
+    /// P at the snapshot
+    pub snapshot_p: u128,
+
+    /// S at the snapshot
+    pub snapshot_s: u128,
+
+    /// Scale at the snapshot
+    pub snapshot_scale: u64,
+
+    /// Bump for the StabilityDeposit PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl StabilityDeposit {
+    pub const SEED_PREFIX: &'static [u8] = b"stability_deposit";
+    pub const LEN: usize = 8
+        + 32 // stability_pool
+        + 32 // depositor
+        + 8  // initial_deposit
+        + 16 // snapshot_p
+        + 16 // snapshot_s
+        + 8  // snapshot_scale
+        + 1  // bump
+        + 7; // _reserved
+}
+
+/// Peg Stability Module config for one approved stablecoin: swaps it 1:1 with PUSD.
+/// PDA seed: ["psm", protocol, stable_mint]
+#[account]
+pub struct PsmConfig {
+    /// Protocol this PSM belongs to
+    pub protocol: Pubkey,
+
+    /// Approved stablecoin mint (e.g., USDC)
+    pub stable_mint: Pubkey,
+
+    /// Vault token account (owned by this PDA) holding swapped-in stablecoins
+    pub vault: Pubkey,
+
+    /// Fee (bps) on stablecoin -> PUSD swaps, paid in PUSD to the treasury
+    pub fee_in_bps: u16,
+
+    /// Fee (bps) on PUSD -> stablecoin swaps, paid in PUSD to the treasury
+    pub fee_out_bps: u16,
+
+    /// Max PUSD outstanding against this asset
+    pub debt_ceiling: u64,
+
+    /// PUSD currently outstanding against this asset (counts toward global debt)
+    pub total_minted_pusd: u64,
+
+    /// Active flag (false disables swaps in)
+    pub active: bool,
+
+    /// Bump for the PsmConfig PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 6],
+}
+impl PsmConfig {
+    pub const SEED_PREFIX: &'static [u8] = b"psm";
+    pub const VAULT_SEED_PREFIX: &'static [u8] = b"psm_vault";
+    pub const LEN: usize = 8
+        + 32 // protocol
+        + 32 // stable_mint
+        + 32 // vault
+        + 2  // fee_in_bps
+        + 2  // fee_out_bps
+        + 8  // debt_ceiling
+        + 8  // total_minted_pusd
+        + 1  // active
+        + 1  // bump
+        + 6; // _reserved
+}
+
+/// Protocol treasury: owns the PUSD token account that receives realized fees.
+/// PDA seed: ["treasury", protocol]
+#[account]
+pub struct Treasury {
+    /// Protocol this treasury belongs to
+    pub protocol: Pubkey,
+
+    /// PUSD token account (owned by this PDA) holding the surplus buffer
+    pub pusd_account: Pubkey,
+
+    /// PUSD token account that governance sweeps surplus to
+    pub surplus_destination: Pubkey,
+
+    /// PUSD kept in the treasury as a buffer; only the excess can be swept
+    pub surplus_buffer_pusd: u64,
+
+    /// Cumulative PUSD swept to `surplus_destination`
+    pub total_swept_pusd: u64,
+
+    /// Bump for the Treasury PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl Treasury {
+    pub const SEED_PREFIX: &'static [u8] = b"treasury";
+    pub const PUSD_ACCOUNT_SEED_PREFIX: &'static [u8] = b"treasury_pusd";
+    pub const LEN: usize = 8
+        + 32 // protocol
+        + 32 // pusd_account
+        + 32 // surplus_destination
+        + 8  // surplus_buffer_pusd
+        + 8  // total_swept_pusd
+        + 1  // bump
+        + 7; // _reserved
+}
+
+/// sPUSD savings vault: holds deposited PUSD and mints sPUSD shares against it.
+/// Each share is worth `savings_index` PUSD; the index grows at `savings_rate_bps`, paid
+/// from the treasury's surplus buffer.
+/// PDA seed: ["savings", protocol]
+#[account]
+pub struct SavingsVault {
+    /// Protocol this vault belongs to
+    pub protocol: Pubkey,
+
+    /// sPUSD share mint (mint authority is this PDA)
+    pub share_mint: Pubkey,
+
+    /// PUSD token account (owned by this PDA) holding deposits and paid interest
+    pub pusd_account: Pubkey,
+
+    /// Governance-set savings rate (bps per year)
+    pub savings_rate_bps: u16,
+
+    /// PUSD per sPUSD (RATE_INDEX_ONE = 1.0)
+    pub savings_index: u128,
+
+    /// Last time (Unix ts) the savings index was accrued
+    pub last_accrual_ts: i64,
+
+    /// Cumulative interest paid into the vault from the treasury
+    pub total_interest_pusd: u64,
+
+    /// Bump for the SavingsVault PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl SavingsVault {
+    pub const SEED_PREFIX: &'static [u8] = b"savings";
+    pub const SHARE_MINT_SEED_PREFIX: &'static [u8] = b"spusd_mint";
+    pub const PUSD_ACCOUNT_SEED_PREFIX: &'static [u8] = b"savings_pusd";
+    pub const LEN: usize = 8
+        + 32 // protocol
+        + 32 // share_mint
+        + 32 // pusd_account
+        + 2  // savings_rate_bps
+        + 16 // savings_index
+        + 8  // last_accrual_ts
+        + 8  // total_interest_pusd
+        + 1  // bump
+        + 7; // _reserved
+}
+
 /// Governance account holding timelock and upgrade controls (optional).
 /// PDA seed: ["governance", protocol]
 #[account]
 pub struct Governance {
-    /// The protocol this governance controls
+    /// The protocol this governance controls (changes are queued by `Protocol::risk_admin`)
     pub protocol: Pubkey,
 
-    /// Authority with power to queue/execute parameter changes
-    pub authority: Pubkey,
-
     /// Timelock delay in seconds for critical changes
     pub timelock_secs: u64,
 
@@ -143,12 +782,132 @@ impl Governance {
     pub const SEED_PREFIX: &'static [u8] = b"governance";
     pub const LEN: usize = 8
         + 32 // protocol
-        + 32 // authority
         + 8  // timelock_secs
         + 1  // bump
         + 7; // _reserved
 }
 
+/// A queued governance change; executable between `eta` and `eta + PROPOSAL_GRACE_PERIOD_SECS`.
+/// Closed (rent back to the proposer) when executed or cancelled.
+/// PDA seed: ["proposal", governance, proposal_id (le bytes)]
+#[account]
+pub struct Proposal {
+    /// Governance this proposal belongs to
+    pub governance: Pubkey,
+
+    /// Who queued the change (receives the rent back)
+    pub proposer: Pubkey,
+
+    /// Caller-chosen id, unique among open proposals
+    pub proposal_id: u64,
+
+    /// The parameter change to apply
+    pub action: GovernanceAction,
+
+    /// Earliest execution time (Unix ts)
+    pub eta: i64,
+
+    /// Bump for the Proposal PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl Proposal {
+    pub const SEED_PREFIX: &'static [u8] = b"proposal";
+    pub const LEN: usize = 8
+        + 32 // governance
+        + 32 // proposer
+        + 8  // proposal_id
+        + GovernanceAction::LEN // action
+        + 8  // eta
+        + 1  // bump
+        + 7; // _reserved
+}
+
+/// Global settlement state, created by `cage`.
+/// PDA seed: ["settlement", protocol]
+#[account]
+pub struct Settlement {
+    /// Protocol being wound down
+    pub protocol: Pubkey,
+
+    /// When `cage` was called (Unix ts)
+    pub caged_at: i64,
+
+    /// When `thaw` fixed the PUSD supply (0 until then)
+    pub thawed_at: i64,
+
+    /// Outstanding PUSD at `thaw`; each unit is owed the same share of every collateral
+    pub pusd_supply: u64,
+
+    /// Bump for the Settlement PDA
+    pub bump: u8,
+
+    /// Reserved
+    pub _reserved: [u8; 7],
+}
+impl Settlement {
+    pub const SEED_PREFIX: &'static [u8] = b"settlement";
+    pub const LEN: usize = 8
+        + 32 // protocol
+        + 8  // caged_at
+        + 8  // thawed_at
+        + 8  // pusd_supply
+        + 1  // bump
+        + 7; // _reserved
+}
+
+/// PUSD a holder has packed (burned) for settlement.
+/// PDA seed: ["settlement_claim", settlement, owner]
+#[account]
+pub struct SettlementClaim {
+    /// Settlement this claim belongs to
+    pub settlement: Pubkey,
+
+    /// Holder who packed the PUSD
+    pub owner: Pubkey,
+
+    /// Total PUSD packed
+    pub pusd_packed: u64,
+
+    /// Bump for the SettlementClaim PDA
+    pub bump: u8,
+}
+impl SettlementClaim {
+    pub const SEED_PREFIX: &'static [u8] = b"settlement_claim";
+    pub const LEN: usize = 8
+        + 32 // settlement
+        + 32 // owner
+        + 8  // pusd_packed
+        + 1; // bump
+}
+
+/// How much of a claim has been cashed against one collateral.
+/// PDA seed: ["settlement_cash", claim, collateral_config]
+#[account]
+pub struct SettlementCash {
+    /// Claim being cashed
+    pub claim: Pubkey,
+
+    /// Collateral paid out
+    pub collateral_config: Pubkey,
+
+    /// Packed PUSD already cashed against this collateral
+    pub cashed_pusd: u64,
+
+    /// Bump for the SettlementCash PDA
+    pub bump: u8,
+}
+impl SettlementCash {
+    pub const SEED_PREFIX: &'static [u8] = b"settlement_cash";
+    pub const LEN: usize = 8
+        + 32 // claim
+        + 32 // collateral_config
+        + 8  // cashed_pusd
+        + 1; // bump
+}
+
 /// Helper: checked math for LTV (basis points).
 pub fn compute_health_bps(
     collateral_value_usd_6dp: u128, // value of collateral in 6-decimal USD
@@ -164,8 +923,8 @@ pub fn compute_health_bps(
 }
 
 /// Seeds helpers to be reused in instructions
-pub fn protocol_seeds<'a>(authority: &'a Pubkey, bump: u8) -> [&'a [u8]; 3] {
-    [Protocol::SEED_PREFIX, authority.as_ref(), &[bump]]
+pub fn protocol_seeds<'a>(seed_key: &'a Pubkey, bump: u8) -> [&'a [u8]; 3] {
+    [Protocol::SEED_PREFIX, seed_key.as_ref(), &[bump]]
 }
 
 pub fn collateral_seeds<'a>(
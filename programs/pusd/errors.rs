@@ -10,19 +10,58 @@ pub enum ErrorCode {
     #[msg("Invalid PDA seeds or bump.")]
     InvalidPda,
 
+    #[msg("Protocol account is already on the current layout.")]
+    ProtocolAlreadyMigrated,
+
     #[msg("Governance timelock has not elapsed.")]
     TimelockNotElapsed,
 
+    #[msg("Change must go through the governance timelock.")]
+    TimelockRequired,
+
+    #[msg("Governance proposal expired; queue it again.")]
+    ProposalExpired,
+
+    #[msg("Account required by this governance action is missing or does not match.")]
+    GovernanceTargetMismatch,
+
     // -------- Params / Config --------
     #[msg("Protocol minting is currently paused.")]
     MintPaused,
 
+    #[msg("Collateral deposits are currently paused.")]
+    DepositPaused,
+
+    #[msg("PUSD repayment is currently paused.")]
+    BurnPaused,
+
+    #[msg("Collateral withdrawals are currently paused.")]
+    WithdrawPaused,
+
+    #[msg("Liquidations are currently paused.")]
+    LiquidationPaused,
+
+    #[msg("PSM swaps are currently paused.")]
+    PsmPaused,
+
+    #[msg("Protocol is in global settlement.")]
+    ProtocolCaged,
+
+    #[msg("Protocol is not in global settlement.")]
+    ProtocolNotCaged,
+
+    #[msg("Settlement step is not available yet.")]
+    SettlementNotReady,
+
     #[msg("Collateral type is not active.")]
     CollateralInactive,
 
     #[msg("Unsupported collateral mint.")]
     UnsupportedCollateral,
 
+    #[msg("Cross-margin account holds the maximum number of collateral types.")]
+    CrossMarginFull,
+
     #[msg("Global debt ceiling reached.")]
     GlobalDebtCeilingReached,
 
@@ -32,6 +71,12 @@ pub enum ErrorCode {
     #[msg("Parameter out of allowed bounds.")]
     InvalidParameter,
 
+    #[msg("Parameter change exceeds the maximum step per update.")]
+    ParameterStepTooLarge,
+
+    #[msg("Risk parameters were changed too recently.")]
+    RiskUpdateTooSoon,
+
     // -------- Amounts / Math --------
     #[msg("Amount must be greater than zero.")]
     ZeroAmount,
@@ -67,6 +112,9 @@ pub enum ErrorCode {
     #[msg("Provided vault account does not match config.")]
     VaultMismatch,
 
+    #[msg("Account has already been settled.")]
+    AlreadySettled,
+
     // -------- Liquidation --------
     #[msg("Position is not eligible for liquidation.")]
     NotLiquidatable,
@@ -77,6 +125,25 @@ pub enum ErrorCode {
     #[msg("Slippage or price impact exceeded limits.")]
     SlippageExceeded,
 
+    #[msg("An auction is already running for this position.")]
+    AuctionActive,
+
+    #[msg("No auction is running for this position.")]
+    AuctionNotActive,
+
+    #[msg("Auction has not run its full duration yet.")]
+    AuctionNotExpired,
+
+    #[msg("Stability pool has no deposits available to absorb this liquidation.")]
+    StabilityPoolEmpty,
+
+    // -------- Redemptions --------
+    #[msg("Redemptions must target positions in the riskiest non-empty bucket first.")]
+    RedemptionOrderViolated,
+
+    #[msg("Position cannot be redeemed against.")]
+    RedemptionTargetInvalid,
+
     // -------- Oracles / Pricing --------
     #[msg("Oracle account(s) invalid or missing.")]
     InvalidOracle,
@@ -87,6 +154,12 @@ pub enum ErrorCode {
     #[msg("Oracle price out of acceptable bounds.")]
     PriceOutOfBounds,
 
+    #[msg("Oracle confidence interval too wide relative to price.")]
+    OracleConfidenceTooWide,
+
+    #[msg("Stake pool exchange rate moved too far since its previous epoch.")]
+    StakeRateOutOfBounds,
+
     // -------- SPL / Token --------
     #[msg("Token mint does not match expected mint.")]
     MintMismatch,
@@ -94,6 +167,23 @@ pub enum ErrorCode {
     #[msg("Token account owner mismatch.")]
     TokenOwnerMismatch,
 
+    #[msg("Collateral mint carries an unsupported Token-2022 extension.")]
+    UnsupportedMintExtension,
+
+    // -------- Peg Stability Module --------
+    #[msg("PSM debt ceiling reached for this asset.")]
+    PsmCeilingReached,
+
+    #[msg("PSM vault does not hold enough of this asset.")]
+    PsmInsufficientLiquidity,
+
+    // -------- Treasury --------
+    #[msg("Treasury account does not match protocol configuration.")]
+    TreasuryMismatch,
+
+    #[msg("Not enough surplus above the treasury buffer.")]
+    InsufficientSurplus,
+
     // -------- Misc --------
     #[msg("Feature not implemented.")]
     NotImplemented,
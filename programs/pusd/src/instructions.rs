@@ -1,29 +1,76 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{
-    self, Burn, Mint, MintTo, Token, TokenAccount, Transfer,
+use anchor_lang::{system_program, Discriminator};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::token_2022::spl_token_2022::extension::{metadata_pointer, ExtensionType};
+use anchor_spl::token_2022::{self, spl_token_2022, InitializeMint2, Token2022};
+use anchor_spl::token_interface::{
+    self, Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface,
 };
+use spl_token_metadata_interface::state::TokenMetadata;
 
 use crate::accounts::*;
 use crate::errors::ErrorCode;
+use crate::oracle::{
+    collateral_price, load_oracle_price, load_stake_pool_rate, require_oracle_owner,
+    SPL_STAKE_POOL_PROGRAM_ID,
+};
 use crate::state::{
-    apply_liquidation_bonus_bps, check_mint_within_initial_ltv, compute_health_bps,
-    is_above_maintenance, token_amount_to_usd_6dp, CollateralAdded, Initialized, Liquidated,
-    Minted, PauseToggled, Burned, ParameterUpdated, PUSD_DECIMALS,
+    accrue_rate_index, accrue_savings_index, apply_liquidation_bonus_bps, apply_pause,
+    auction_curve_6dp, auction_expired,
+    bad_debt_cover_split,
+    bump_redemption_base_rate, check_mint_within_initial_ltv, collateral_mint_extensions_safe,
+    collateral_risk_params_valid, compute_health_bps, convert_decimals,
+    cross_margin_above_maintenance, cross_margin_within_initial_ltv, debt_after_mint,
+    debt_ceiling_step_ok, debt_to_normalized, decay_redemption_base_rate, dutch_auction_price_6dp,
+    is_above_maintenance,
+    is_action_paused, normalized_to_debt, pack_label, pusd_metadata_valid, realize_accrued_surplus,
+    redemption_bucket,
+    redemption_fee_bps, redemption_order_ok, repay_to_target_health, risk_update_due,
+    savings_pusd_for_shares,
+    savings_shares_for_pusd,
+    settlement_cash_amount, settlement_collateral_owed, settlement_pusd_supply, sweepable_surplus,
+    token_amount_to_usd_6dp,
+    unbacked_debt, within_step,
+    AuctionRestarted, AuctionSettled, AuctionStarted, AuctionTaken, AuthorityProposed,
+    AuthorityTransferred,
+    BadDebtCovered, BadDebtRecognized, Burned, Caged, ChangeCancelled, ChangeExecuted, ChangeQueued,
+    CollateralAdded, CollateralCaged, CollateralParamsUpdate, CollateralPricingMode,
+    CollateralRiskParams,
+    CollateralWithdrawn, CrossMarginBalance, CrossMarginBurned, CrossMarginDeposited,
+    CrossMarginLiquidated, CrossMarginMinted, CrossMarginOpened, CrossMarginSettled,
+    CrossMarginWithdrawn, FeeBooked, GovernanceAction, GovernanceInitialized, Initialized,
+    Liquidated, LiquidationPriceMode, Minted, OracleFeed, OracleParams, ParameterUpdated,
+    PauseToggled, PositionSettled, ProtocolMigrated, ProtocolRole, PsmAssetAdded, PsmParamsUpdate,
+    PsmSwappedIn, PsmSwappedOut, PusdPacked, Redeemed, RoleUpdated, SavingsAccrued,
+    SavingsDeposited, SavingsInitialized, SavingsWithdrawn, SettlementCashed, SettlementPhase,
+    StabilityFeeAccrued,
+    StabilityPoolAccumulators, StabilityPoolDeposited, StabilityPoolOffset, StabilityPoolWithdrawn,
+    SurplusSwept, Thawed, TreasuryInitialized, BPS_DENOMINATOR, FEE_SOURCE_LIQ_PENALTY,
+    FEE_SOURCE_PSM, FEE_SOURCE_REALIZED, FEE_SOURCE_STABILITY_FEE, MAX_CROSS_MARGIN_COLLATERALS,
+    MAX_LIQ_BONUS_STEP_BPS, MAX_LIQ_PENALTY_BPS, MAX_LTV_STEP_BPS, MAX_ORACLES_PER_COLLATERAL,
+    MAX_PSM_FEE_BPS, MAX_SAVINGS_RATE_BPS,
+    MAX_STABILITY_FEE_BPS, MAX_TIMELOCK_SECS, MIN_STABILITY_POOL_DEPOSITS, MIN_TIMELOCK_SECS,
+    PAUSE_ALL, PAUSE_BURN,
+    PAUSE_COLLATERAL_ACTIONS, PAUSE_DEPOSIT, PAUSE_LIQUIDATE, PAUSE_MINT, PAUSE_PSM, PAUSE_WITHDRAW,
+    PROPOSAL_GRACE_PERIOD_SECS, PUSD_DECIMALS, RATE_INDEX_ONE, REDEMPTION_BUCKETS,
+    REDEMPTION_BUCKET_NONE, SETTLEMENT_THAW_DELAY_SECS, SOL_DECIMALS, SP_PRECISION,
 };
 
 /// ===============================
 /// Handlers
 /// ===============================
 
-/// Initialize the protocol and bind the PUSD mint (mint authority must be the Protocol PDA).
+/// Initialize the protocol and bind an existing PUSD mint, co-signed by the mint keypair
+/// (mint authority must be the Protocol PDA).
 pub fn handle_initialize(ctx: Context<Initialize>, global_debt_ceiling: u64) -> Result<()> {
     let protocol = &mut ctx.accounts.protocol;
-
-    protocol.authority = ctx.accounts.authority.key();
-    protocol.bump = *ctx.bumps.get("protocol").ok_or(ErrorCode::InvalidPda)?;
-    protocol.pusd_mint = ctx.accounts.pusd_mint.key();
-    protocol.global_debt_ceiling = global_debt_ceiling;
-    protocol.mint_paused = false;
+    init_protocol_state(
+        protocol,
+        ctx.accounts.authority.key(),
+        ctx.accounts.pusd_mint.key(),
+        *ctx.bumps.get("protocol").ok_or(ErrorCode::InvalidPda)?,
+        global_debt_ceiling,
+    );
 
     // Sanity: The PUSD mint authority must be the protocol PDA
     require!(
@@ -41,37 +88,345 @@ pub fn handle_initialize(ctx: Context<Initialize>, global_debt_ceiling: u64) ->
     Ok(())
 }
 
-/// Register a collateral type and its vault.
+/// Initialize the protocol and create the PUSD mint under Token-2022, with the
+/// metadata-pointer and token-metadata extensions (name, symbol, URI) held by the mint
+/// itself. The Protocol PDA is the mint authority and the metadata update authority.
+pub fn handle_initialize_token_2022(
+    ctx: Context<InitializeToken2022>,
+    global_debt_ceiling: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    require!(
+        pusd_metadata_valid(&name, &symbol, &uri),
+        ErrorCode::InvalidParameter
+    );
+
+    let protocol_key = ctx.accounts.protocol.key();
+    let mint_key = ctx.accounts.pusd_mint.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let bump = *ctx.bumps.get("protocol").ok_or(ErrorCode::InvalidPda)?;
+
+    // Allocate for the metadata pointer only; token-metadata reallocs the mint when it is
+    // initialized, so its rent is funded upfront
+    let mint_space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::MetadataPointer,
+    ])?;
+    let metadata = TokenMetadata {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        ..Default::default()
+    };
+    let metadata_space = metadata.tlv_size_of()?;
+    let lamports = Rent::get()?.minimum_balance(
+        mint_space
+            .checked_add(metadata_space)
+            .ok_or(ErrorCode::MathOverflow)?,
+    );
+
+    let cpi_accounts = system_program::CreateAccount {
+        from: ctx.accounts.authority.to_account_info(),
+        to: ctx.accounts.pusd_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+    system_program::create_account(cpi_ctx, lamports, mint_space as u64, &token_program_id)?;
+
+    // Metadata lives in the mint account; the Protocol PDA may repoint it
+    let ix = metadata_pointer::instruction::initialize(
+        &token_program_id,
+        &mint_key,
+        Some(protocol_key),
+        Some(mint_key),
+    )?;
+    invoke(&ix, &[ctx.accounts.pusd_mint.to_account_info()])?;
+
+    let cpi_accounts = InitializeMint2 {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_2022::initialize_mint2(cpi_ctx, PUSD_DECIMALS as u8, &protocol_key, None)?;
+
+    // Token metadata must be signed by the mint authority (the Protocol PDA)
+    let seeds = [Protocol::SEED_PREFIX, mint_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+    let ix = spl_token_metadata_interface::instruction::initialize(
+        &token_program_id,
+        &mint_key,
+        &protocol_key,
+        &mint_key,
+        &protocol_key,
+        name,
+        symbol,
+        uri,
+    );
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.pusd_mint.to_account_info(),
+            ctx.accounts.protocol.to_account_info(),
+        ],
+        signer,
+    )?;
+
+    let protocol = &mut ctx.accounts.protocol;
+    init_protocol_state(
+        protocol,
+        ctx.accounts.authority.key(),
+        mint_key,
+        bump,
+        global_debt_ceiling,
+    );
+
+    emit!(Initialized {
+        protocol: protocol_key,
+        pusd_mint: mint_key,
+        authority: protocol.authority,
+        global_debt_ceiling
+    });
+
+    Ok(())
+}
+
+/// Fresh Protocol state shared by both initialization paths. All roles start with `authority`.
+pub fn init_protocol_state(
+    protocol: &mut Protocol,
+    authority: Pubkey,
+    pusd_mint: Pubkey,
+    bump: u8,
+    global_debt_ceiling: u64,
+) {
+    protocol.authority = authority;
+    protocol.bump = bump;
+    protocol.pusd_mint = pusd_mint;
+    protocol.global_debt_ceiling = global_debt_ceiling;
+    protocol.total_debt_pusd = 0;
+    protocol.accrued_surplus_pusd = 0;
+    protocol.realized_fees_pusd = 0;
+    protocol.bad_debt_pusd = 0;
+    protocol.paused_actions = 0;
+    protocol.governance = Pubkey::default();
+    protocol.guardian = protocol.authority;
+    protocol.risk_admin = protocol.authority;
+    protocol.treasury_authority = protocol.authority;
+    protocol.pending_authority = Pubkey::default();
+    protocol.seed_key = protocol.pusd_mint;
+    protocol.settlement_phase = SettlementPhase::Live;
+}
+
+/// Register a collateral type, its vault and its oracle sources.
+/// Oracle accounts are passed as remaining accounts, in the same order as `oracle_kinds`.
+/// In StakePool pricing mode the oracles price SOL and the stake pool account follows them.
 pub fn handle_add_collateral(
     ctx: Context<AddCollateral>,
-    initial_ltv_bps: u16,
-    maintenance_ltv_bps: u16,
-    liq_bonus_bps: u16,
-    debt_ceiling: u64,
+    risk_params: CollateralRiskParams,
+    oracle_params: OracleParams,
     active: bool,
 ) -> Result<()> {
+    let CollateralRiskParams {
+        initial_ltv_bps,
+        maintenance_ltv_bps,
+        liq_bonus_bps,
+        liq_penalty_bps,
+        close_factor_bps,
+        target_health_bps,
+        dust_threshold_pusd,
+        debt_ceiling,
+        stability_fee_bps,
+        redemption_fee_floor_bps,
+        auction_start_premium_bps,
+        auction_floor_bps,
+        auction_duration_secs,
+    } = risk_params;
+    let OracleParams {
+        oracle_kinds,
+        min_fresh_oracles,
+        max_oracle_deviation_bps,
+        conf_multiplier_bps,
+        max_conf_ratio_bps,
+        liquidation_price_mode,
+        pricing_mode,
+        max_stake_rate_change_bps,
+    } = oracle_params;
+
     // Basic param checks
-    require!(initial_ltv_bps > 0 && maintenance_ltv_bps > 0, ErrorCode::InvalidParameter);
-    require!(maintenance_ltv_bps <= initial_ltv_bps, ErrorCode::InvalidParameter);
+    require!(
+        collateral_risk_params_valid(initial_ltv_bps, maintenance_ltv_bps, liq_bonus_bps),
+        ErrorCode::InvalidParameter
+    );
+    require!(stability_fee_bps <= MAX_STABILITY_FEE_BPS, ErrorCode::InvalidParameter);
+    require!(liq_penalty_bps <= MAX_LIQ_PENALTY_BPS, ErrorCode::InvalidParameter);
+    require!(
+        close_factor_bps > 0 && close_factor_bps as u128 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        target_health_bps == 0 || target_health_bps > maintenance_ltv_bps,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        auction_floor_bps > 0 && auction_floor_bps <= auction_start_premium_bps,
+        ErrorCode::InvalidParameter
+    );
+    require!(auction_duration_secs > 0, ErrorCode::InvalidParameter);
+    // Token-2022 collateral must not be seizable, frozen in place or gated by an unknown hook
+    require!(
+        collateral_mint_extensions_safe(&ctx.accounts.collateral_mint.to_account_info().try_borrow_data()?),
+        ErrorCode::UnsupportedMintExtension
+    );
+    // Under the timelock, new collateral is listed inactive and enabled by a queued change
+    require!(
+        !active || ctx.accounts.protocol.governance == Pubkey::default(),
+        ErrorCode::TimelockRequired
+    );
+    require!(
+        redemption_fee_floor_bps as u128 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidParameter
+    );
+
+    // Oracle set checks
+    let oracle_count = oracle_kinds.len();
+    require!(
+        oracle_count > 0 && oracle_count <= MAX_ORACLES_PER_COLLATERAL,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        min_fresh_oracles > 0 && min_fresh_oracles as usize <= oracle_count,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        max_oracle_deviation_bps > 0 && max_oracle_deviation_bps as u128 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        max_conf_ratio_bps > 0 && max_conf_ratio_bps as u128 <= BPS_DENOMINATOR,
+        ErrorCode::InvalidParameter
+    );
+    let price_account_count = match pricing_mode {
+        CollateralPricingMode::Direct => oracle_count,
+        CollateralPricingMode::StakePool => oracle_count + 1,
+    };
+    require!(
+        ctx.remaining_accounts.len() == price_account_count,
+        ErrorCode::InvalidOracle
+    );
+
+    // Stake pool tokens are priced off SOL, so the pool must back this mint and share SOL's decimals
+    let mut stake_pool = Pubkey::default();
+    if pricing_mode == CollateralPricingMode::StakePool {
+        require!(
+            max_stake_rate_change_bps > 0 && max_stake_rate_change_bps as u128 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidParameter
+        );
+        require!(
+            ctx.accounts.collateral_mint.decimals == SOL_DECIMALS,
+            ErrorCode::InvalidParameter
+        );
+        let pool = &ctx.remaining_accounts[oracle_count];
+        require_keys_eq!(*pool.owner, SPL_STAKE_POOL_PROGRAM_ID, ErrorCode::InvalidOracle);
+        let rate = {
+            let data = pool.try_borrow_data()?;
+            load_stake_pool_rate(&data)?
+        };
+        require_keys_eq!(
+            rate.pool_mint,
+            ctx.accounts.collateral_mint.key(),
+            ErrorCode::MintMismatch
+        );
+        require!(rate.rate().is_some(), ErrorCode::InvalidOracle);
+        stake_pool = pool.key();
+    }
+
+    // Each oracle must be owned by its oracle program and decode under its selected adapter
+    // before we bind it
+    let mut oracles = [OracleFeed::default(); MAX_ORACLES_PER_COLLATERAL];
+    for (i, (kind, oracle)) in oracle_kinds.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+        require_oracle_owner(*kind, oracle)?;
+        {
+            let data = oracle.try_borrow_data()?;
+            load_oracle_price(*kind, &data)?;
+        }
+        require!(
+            oracles[..i].iter().all(|feed| feed.account != oracle.key()),
+            ErrorCode::InvalidOracle
+        );
+        oracles[i] = OracleFeed {
+            kind: *kind,
+            account: oracle.key(),
+        };
+    }
 
     let cfg = &mut ctx.accounts.collateral_config;
     cfg.protocol = ctx.accounts.protocol.key();
     cfg.collateral_mint = ctx.accounts.collateral_mint.key();
     cfg.vault = ctx.accounts.vault.key();
+    cfg.oracles = oracles;
+    cfg.oracle_count = oracle_count as u8;
+    cfg.min_fresh_oracles = min_fresh_oracles;
+    cfg.max_oracle_deviation_bps = max_oracle_deviation_bps;
+    cfg.conf_multiplier_bps = conf_multiplier_bps;
+    cfg.max_conf_ratio_bps = max_conf_ratio_bps;
+    cfg.liquidation_price_mode = liquidation_price_mode;
+    cfg.pricing_mode = pricing_mode;
+    cfg.stake_pool = stake_pool;
+    cfg.max_stake_rate_change_bps = max_stake_rate_change_bps;
     cfg.initial_ltv_bps = initial_ltv_bps;
     cfg.maintenance_ltv_bps = maintenance_ltv_bps;
     cfg.liq_bonus_bps = liq_bonus_bps;
+    cfg.liq_penalty_bps = liq_penalty_bps;
+    cfg.close_factor_bps = close_factor_bps;
+    cfg.target_health_bps = target_health_bps;
+    cfg.dust_threshold_pusd = dust_threshold_pusd;
+    cfg.auction_start_premium_bps = auction_start_premium_bps;
+    cfg.auction_floor_bps = auction_floor_bps;
+    cfg.auction_duration_secs = auction_duration_secs;
     cfg.debt_ceiling = debt_ceiling;
+    cfg.total_debt_pusd = 0;
+    cfg.total_collateral = 0;
+    cfg.stability_fee_bps = stability_fee_bps;
+    cfg.rate_index = RATE_INDEX_ONE;
+    cfg.last_accrual_ts = Clock::get()?.unix_timestamp;
+    cfg.total_normalized_debt = 0;
+    cfg.redemption_fee_floor_bps = redemption_fee_floor_bps;
+    cfg.redemption_base_rate = 0;
+    cfg.last_redemption_ts = cfg.last_accrual_ts;
+    cfg.redemption_buckets = [0; REDEMPTION_BUCKETS];
+    cfg.final_price_6dp = 0;
+    cfg.settlement_collateral = 0;
+    cfg.settlement_shortfall_pusd = 0;
+    cfg.last_risk_update_ts = cfg.last_accrual_ts;
     cfg.active = active;
+    cfg.paused_actions = 0;
     cfg.bump = *ctx.bumps.get("collateral_config").ok_or(ErrorCode::InvalidPda)?;
+    cfg.cross_margin_balances = 0;
 
     emit!(CollateralAdded {
         protocol: cfg.protocol,
         collateral_mint: cfg.collateral_mint,
         vault: cfg.vault,
+        oracles: cfg.oracles,
+        oracle_count: cfg.oracle_count,
+        min_fresh_oracles,
+        max_oracle_deviation_bps,
+        conf_multiplier_bps,
+        max_conf_ratio_bps,
+        liquidation_price_mode,
+        stability_fee_bps,
         initial_ltv_bps,
         maintenance_ltv_bps,
         liq_bonus_bps,
+        liq_penalty_bps,
+        close_factor_bps,
+        target_health_bps,
+        dust_threshold_pusd,
+        auction_start_premium_bps,
+        auction_floor_bps,
+        auction_duration_secs,
+        redemption_fee_floor_bps,
+        pricing_mode,
+        stake_pool,
+        max_stake_rate_change_bps,
         debt_ceiling,
     });
 
@@ -84,54 +439,70 @@ pub fn handle_open_or_fund_position(
     deposit_amount: u64,
 ) -> Result<()> {
     require!(deposit_amount > 0, ErrorCode::ZeroAmount);
-    let cfg = &ctx.accounts.collateral_config;
+    let cfg = &mut ctx.accounts.collateral_config;
     require!(cfg.active, ErrorCode::CollateralInactive);
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_DEPOSIT)?;
 
-    // Transfer collateral from user to protocol vault
-    let cpi_accounts = Transfer {
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+
+    // Transfer collateral from user to protocol vault. A transfer-fee mint delivers less than
+    // `deposit_amount`, so credit only what the vault actually received.
+    let vault_before = ctx.accounts.vault.amount;
+    let cpi_accounts = token_interface::TransferChecked {
         from: ctx.accounts.user_collateral_ata.to_account_info(),
+        mint: ctx.accounts.collateral_mint.to_account_info(),
         to: ctx.accounts.vault.to_account_info(),
         authority: ctx.accounts.owner.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, deposit_amount)?;
+    token_interface::transfer_checked(cpi_ctx, deposit_amount, ctx.accounts.collateral_mint.decimals)?;
+    ctx.accounts.vault.reload()?;
+    let received = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(received > 0, ErrorCode::ZeroAmount);
 
     // Initialize or update position
     let pos = &mut ctx.accounts.position;
     if pos.owner == Pubkey::default() {
         pos.owner = ctx.accounts.owner.key();
         pos.collateral_config = cfg.key();
-        pos.collateral_amount = deposit_amount;
+        pos.collateral_amount = received;
         pos.debt_pusd = 0;
-        pos.last_accrual_ts = Clock::get()?.unix_timestamp;
+        pos.normalized_debt = 0;
+        pos.last_accrual_ts = now_ts;
+        pos.redemption_bucket = REDEMPTION_BUCKET_NONE;
         pos.bump = *ctx.bumps.get("position").ok_or(ErrorCode::InvalidPda)?;
     } else {
         require!(pos.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+        sync_position_debt(pos, cfg, now_ts)?;
         pos.collateral_amount = pos
             .collateral_amount
-            .checked_add(deposit_amount)
+            .checked_add(received)
             .ok_or(ErrorCode::MathOverflow)?;
+        reindex_position(cfg, pos);
     }
 
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_add(received)
+        .ok_or(ErrorCode::MathOverflow)?;
+
     Ok(())
 }
 
 /// Mint PUSD against deposited collateral.
-/// TEMP: we pass `collateral_price_usd_6dp` (price of 1 whole collateral token in 6dp USD)
-/// and `collateral_decimals` until oracle wiring is added.
-pub fn handle_mint(
-    ctx: Context<MintPusd>,
-    mint_pusd_6dp: u64,
-    collateral_price_usd_6dp: u128,
-    collateral_decimals: u8,
-) -> Result<()> {
+/// Collateral is valued with the registered oracles (passed as remaining accounts);
+/// decimals come from the collateral mint.
+pub fn handle_mint(ctx: Context<MintPusd>, mint_pusd_6dp: u64) -> Result<()> {
     require!(mint_pusd_6dp > 0, ErrorCode::ZeroAmount);
-
-    let protocol = &mut ctx.accounts.protocol;
-    require!(!protocol.mint_paused, ErrorCode::MintPaused);
-
-    let cfg = &ctx.accounts.collateral_config;
+    let cfg = &mut ctx.accounts.collateral_config;
     require!(cfg.active, ErrorCode::CollateralInactive);
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_MINT)?;
     require!(cfg.vault == ctx.accounts.vault.key(), ErrorCode::VaultMismatch);
 
     // Position must belong to owner and this collateral
@@ -139,7 +510,17 @@ pub fn handle_mint(
     require!(pos.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
     require!(pos.collateral_config == cfg.key(), ErrorCode::UnsupportedCollateral);
 
+    // Realize stability fees before checking limits
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
+    let protocol = &ctx.accounts.protocol;
+
     // Compute collateral value and check LTV
+    // Value at the low end of the confidence band so wide bands cannot be used to over-mint
+    let collateral_price_usd_6dp = collateral_price(ctx.remaining_accounts, cfg)?
+        .borrow_price_6dp(cfg.conf_multiplier_bps)?;
+    let collateral_decimals = ctx.accounts.collateral_mint.decimals;
     let collateral_value_6dp = token_amount_to_usd_6dp(
         pos.collateral_amount,
         collateral_decimals,
@@ -158,35 +539,39 @@ pub fn handle_mint(
     );
     require!(within_ltv, ErrorCode::LtvExceeded);
 
-    // Global (and per-collateral) debt checks – simplified: compare against protocol global ceiling
-    let new_total_debt = (protocol.global_debt_ceiling as u128)
-        .checked_sub(0)
-        .ok_or(ErrorCode::MathUnderflow)?; // placeholder (you will track aggregate debt separately)
-    let _ = new_total_debt; // silence warning for now
+    // Global and per-collateral debt ceilings
+    let new_total_debt = debt_after_mint(
+        protocol.total_debt_pusd,
+        mint_pusd_6dp,
+        protocol.global_debt_ceiling,
+    )
+    .ok_or(ErrorCode::GlobalDebtCeilingReached)?;
+
+    let new_collateral_debt = debt_after_mint(cfg.total_debt_pusd, mint_pusd_6dp, cfg.debt_ceiling)
+        .ok_or(ErrorCode::CollateralDebtCeilingReached)?;
 
     // Mint PUSD to the user (PUSD mint authority must be the Protocol PDA)
     let seeds = [
         Protocol::SEED_PREFIX,
-        protocol.authority.as_ref(),
+        protocol.seed_key.as_ref(),
         &[protocol.bump],
     ];
     let signer = &[&seeds[..]];
 
-    let cpi_accounts = MintTo {
+    let cpi_accounts = token_interface::MintTo {
         mint: ctx.accounts.pusd_mint.to_account_info(),
         to: ctx.accounts.user_pusd_ata.to_account_info(),
         authority: ctx.accounts.protocol.to_account_info(),
     };
     let cpi_ctx =
         CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
-    token::mint_to(cpi_ctx, mint_pusd_6dp)?;
+    token_interface::mint_to(cpi_ctx, mint_pusd_6dp)?;
 
-    // Update position debt
-    pos.debt_pusd = pos
-        .debt_pusd
-        .checked_add(mint_pusd_6dp)
-        .ok_or(ErrorCode::MathOverflow)?;
-    pos.last_accrual_ts = Clock::get()?.unix_timestamp;
+    // Update aggregate and position debt
+    ctx.accounts.protocol.total_debt_pusd = new_total_debt;
+    cfg.total_debt_pusd = new_collateral_debt;
+    increase_position_debt(pos, cfg, mint_pusd_6dp)?;
+    reindex_position(cfg, pos);
 
     emit!(Minted {
         owner: pos.owner,
@@ -198,33 +583,41 @@ pub fn handle_mint(
     Ok(())
 }
 
-/// Burn/repay PUSD and (optionally) withdraw collateral later.
-/// Here we only burn; withdrawals can be a separate instruction.
+/// Burn/repay PUSD. Collateral is released separately via `handle_withdraw_collateral`.
 pub fn handle_burn(ctx: Context<BurnPusd>, burn_pusd_6dp: u64) -> Result<()> {
     require!(burn_pusd_6dp > 0, ErrorCode::ZeroAmount);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.collateral_config.paused_actions,
+        PAUSE_BURN,
+    )?;
 
     // Burn PUSD from user
-    let cpi_accounts = Burn {
+    let cpi_accounts = token_interface::Burn {
         mint: ctx.accounts.pusd_mint.to_account_info(),
         from: ctx.accounts.user_pusd_ata.to_account_info(),
         authority: ctx.accounts.owner.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::burn(cpi_ctx, burn_pusd_6dp)?;
+    token_interface::burn(cpi_ctx, burn_pusd_6dp)?;
 
-    // Decrease position debt
+    // Realize stability fees, then decrease position debt
     let pos = &mut ctx.accounts.position;
     require!(pos.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
 
-    pos.debt_pusd = pos
-        .debt_pusd
-        .checked_sub(burn_pusd_6dp)
-        .ok_or(ErrorCode::MathUnderflow)?;
-    pos.last_accrual_ts = Clock::get()?.unix_timestamp;
+    let cfg = &mut ctx.accounts.collateral_config;
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
+    decrease_position_debt(pos, cfg, burn_pusd_6dp)?;
+    reindex_position(cfg, pos);
+
+    // Update aggregate debt
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, burn_pusd_6dp);
 
     emit!(Burned {
         owner: pos.owner,
-        collateral_mint: ctx.accounts.collateral_config.collateral_mint,
+        collateral_mint: cfg.collateral_mint,
         burned_pusd_6dp: burn_pusd_6dp,
         new_debt_pusd_6dp: pos.debt_pusd,
     });
@@ -232,19 +625,99 @@ pub fn handle_burn(ctx: Context<BurnPusd>, burn_pusd_6dp: u64) -> Result<()> {
     Ok(())
 }
 
-/// Liquidate an unhealthy position by repaying PUSD in exchange for discounted collateral.
-/// TEMP: pass `collateral_price_usd_6dp` and `collateral_decimals` until oracles are wired.
-pub fn handle_liquidate(
-    ctx: Context<Liquidate>,
-    repay_pusd_6dp: u64,
-    collateral_price_usd_6dp: u128,
-    collateral_decimals: u8,
+/// Withdraw collateral from a position back to the owner.
+/// The remaining collateral must still satisfy initial LTV for the outstanding debt.
+/// Oracle accounts are only read (as remaining accounts) when the position has debt.
+pub fn handle_withdraw_collateral(
+    ctx: Context<WithdrawCollateral>,
+    withdraw_amount: u64,
 ) -> Result<()> {
-    require!(repay_pusd_6dp > 0, ErrorCode::ZeroAmount);
+    require!(withdraw_amount > 0, ErrorCode::ZeroAmount);
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_WITHDRAW)?;
+    let pos = &mut ctx.accounts.position;
+    require!(pos.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+    require!(pos.collateral_config == cfg.key(), ErrorCode::UnsupportedCollateral);
 
+    // Realize stability fees so the LTV check sees the full debt
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
     let cfg = &ctx.accounts.collateral_config;
+    require!(
+        withdraw_amount <= pos.collateral_amount,
+        ErrorCode::InsufficientCollateral
+    );
+
+    let remaining_collateral = pos
+        .collateral_amount
+        .checked_sub(withdraw_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    // Debt-free positions can always withdraw; otherwise the remainder must back the debt
+    if pos.debt_pusd > 0 {
+        let collateral_price_usd_6dp = collateral_price(ctx.remaining_accounts, cfg)?
+            .borrow_price_6dp(cfg.conf_multiplier_bps)?;
+        check_withdrawal_ltv(
+            pos.collateral_amount,
+            remaining_collateral,
+            pos.debt_pusd,
+            ctx.accounts.collateral_mint.decimals,
+            collateral_price_usd_6dp,
+            cfg.initial_ltv_bps,
+            cfg.maintenance_ltv_bps,
+        )?;
+    }
+
+    // Transfer collateral from vault back to the owner (vault authority is the CollateralConfig PDA)
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.user_collateral_ata.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        withdraw_amount,
+    )?;
+
+    pos.collateral_amount = remaining_collateral;
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(withdraw_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    reindex_position(cfg, pos);
+
+    emit!(CollateralWithdrawn {
+        owner: pos.owner,
+        collateral_mint: cfg.collateral_mint,
+        withdrawn_amount: withdraw_amount,
+        remaining_collateral_amount: pos.collateral_amount,
+    });
+
+    Ok(())
+}
+
+/// Liquidate an unhealthy position by repaying PUSD in exchange for discounted collateral.
+/// Oracle accounts for the collateral are passed as remaining accounts.
+pub fn handle_liquidate(ctx: Context<Liquidate>, repay_pusd_6dp: u64) -> Result<()> {
+    require!(repay_pusd_6dp > 0, ErrorCode::ZeroAmount);
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_LIQUIDATE)?;
     let pos = &mut ctx.accounts.position;
 
+    // Realize stability fees so eligibility is judged on the full debt
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
+    let cfg = &ctx.accounts.collateral_config;
+
+    let collateral_price_usd_6dp = collateral_price(ctx.remaining_accounts, cfg)?
+        .liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode);
+    let collateral_decimals = ctx.accounts.collateral_mint.decimals;
+
     // Check liquidatability: health < maintenance
     let collateral_value_6dp = token_amount_to_usd_6dp(
         pos.collateral_amount,
@@ -260,66 +733,74 @@ pub fn handle_liquidate(
     );
     require!(!healthy, ErrorCode::NotLiquidatable);
 
-    // Cap repay to current debt
+    // Limit how much of the debt one call may repay (close factor / target health), unless the
+    // position is dust-sized and may be closed out entirely
+    let (max_repay, full_liquidation) = max_liquidation_repay(cfg, collateral_value_6dp, pos.debt_pusd)?;
+    require!(
+        full_liquidation || repay_pusd_6dp <= max_repay,
+        ErrorCode::LiquidationTooLarge
+    );
+
+    // Charge the liquidation penalty on the debt being liquidated; it is booked as surplus and
+    // added to the position's debt, so the owner bears it (repayment stays capped at the request)
+    let penalty = liquidation_penalty_pusd(cfg, repay_pusd_6dp.min(pos.debt_pusd))?;
+    if penalty > 0 {
+        let cfg = &mut ctx.accounts.collateral_config;
+        increase_position_debt(pos, cfg, penalty)?;
+        book_fee(&mut ctx.accounts.protocol, cfg, penalty, FEE_SOURCE_LIQ_PENALTY)?;
+    }
+    let cfg = &ctx.accounts.collateral_config;
+
+    // Cap repay to current debt (including the penalty); only reachable on full liquidations
     let repay = repay_pusd_6dp.min(pos.debt_pusd);
 
     // Burn PUSD from liquidator first (repay debt)
-    let cpi_burn = Burn {
+    let cpi_burn = token_interface::Burn {
         mint: ctx.accounts.pusd_mint.to_account_info(),
         from: ctx.accounts.liquidator_pusd_ata.to_account_info(),
         authority: ctx.accounts.liquidator.to_account_info(),
     };
-    let cpi_ctx_burn = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
-    token::burn(cpi_ctx_burn, repay)?;
-
-    // Determine collateral to seize with liquidation bonus
-    // price_6dp = USD per 1 whole token; convert repay USD into token amount (inverse pricing).
-    // tokens = (repay_6dp / price_6dp) * 10^decimals
-    let price_6dp = collateral_price_usd_6dp;
-    require!(price_6dp > 0, ErrorCode::PriceOutOfBounds);
-
-    let repay_u128 = repay as u128;
-    let num = repay_u128
-        .checked_mul(crate::state::ten_pow_u128(collateral_decimals as u32))
-        .ok_or(ErrorCode::MathOverflow)?;
-    let base_tokens = num.checked_div(price_6dp).ok_or(ErrorCode::DivisionByZero)?;
-    let seized_with_bonus =
-        apply_liquidation_bonus_bps(base_tokens, cfg.liq_bonus_bps).ok_or(ErrorCode::MathOverflow)?;
-
-    let seize_amount: u64 = seized_with_bonus
-        .try_into()
-        .map_err(|_| ErrorCode::InvalidAmount)?;
+    let cpi_ctx_burn =
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx_burn, repay)?;
 
-    // Bound by available collateral
-    let seize_amount = seize_amount.min(pos.collateral_amount);
+    // Determine collateral to seize with liquidation bonus, bounded by available collateral
+    let seize_amount = seize_for_repay(
+        repay,
+        collateral_decimals,
+        collateral_price_usd_6dp,
+        cfg.liq_bonus_bps,
+    )?
+    .min(pos.collateral_amount);
     require!(seize_amount > 0, ErrorCode::InvalidAmount);
 
     // Transfer collateral from vault to liquidator
-    let seeds = [
-        CollateralConfig::SEED_PREFIX,
-        cfg.protocol.as_ref(),
-        cfg.collateral_mint.as_ref(),
-        &[cfg.bump],
-    ];
-    // NOTE: vault is owned by the program (via PDA authority). If vault authority is Protocol PDA,
-    // change seeds accordingly. For now we assume vault authority is the CollateralConfig PDA.
-    let signer = &[&seeds[..]];
-
-    let cpi_transfer = Transfer {
-        from: ctx.accounts.vault.to_account_info(),
-        to: ctx.accounts.liquidator_collateral_ata.to_account_info(),
-        authority: ctx.accounts.collateral_config.to_account_info(),
-    };
-    let cpi_ctx_transfer =
-        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_transfer, signer);
-    token::transfer(cpi_ctx_transfer, seize_amount)?;
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.liquidator_collateral_ata.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
+        seize_amount,
+    )?;
 
     // Update position
     pos.collateral_amount = pos
         .collateral_amount
         .checked_sub(seize_amount)
         .ok_or(ErrorCode::MathUnderflow)?;
-    pos.debt_pusd = pos.debt_pusd.checked_sub(repay).ok_or(ErrorCode::MathUnderflow)?;
+
+    // Update aggregates
+    let cfg = &mut ctx.accounts.collateral_config;
+    decrease_position_debt(pos, cfg, repay)?;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, repay);
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(seize_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    recognize_bad_debt(&mut ctx.accounts.protocol, cfg, pos)?;
+    reindex_position(cfg, pos);
 
     emit!(Liquidated {
         liquidator: ctx.accounts.liquidator.key(),
@@ -332,219 +813,5023 @@ pub fn handle_liquidate(
     Ok(())
 }
 
-/// Pause/unpause minting (emergency circuit breaker).
-pub fn handle_toggle_pause(ctx: Context<TogglePause>, paused: bool) -> Result<()> {
-    let protocol = &mut ctx.accounts.protocol;
-    require_keys_eq!(protocol.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
-    protocol.mint_paused = paused;
+/// Permissionless: move an unhealthy position into a Dutch auction.
+/// The whole position is auctioned: its collateral is earmarked for the auction and its debt
+/// (plus the liquidation penalty) becomes the auction's target. Oracle accounts are passed as
+/// remaining accounts, in the order registered on the collateral config.
+pub fn handle_start_auction(ctx: Context<StartAuction>) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    require!(!auction.active, ErrorCode::AuctionActive);
 
-    emit!(PauseToggled {
-        protocol: protocol.key(),
-        paused,
-    });
+    let cfg = &mut ctx.accounts.collateral_config;
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_LIQUIDATE)?;
+    let pos = &mut ctx.accounts.position;
+    require!(pos.collateral_config == cfg.key(), ErrorCode::UnsupportedCollateral);
 
-    Ok(())
-}
+    // Realize stability fees so eligibility is judged on the full debt
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
+    require!(pos.debt_pusd > 0 && pos.collateral_amount > 0, ErrorCode::NotLiquidatable);
 
-/// ===============================
-/// Accounts
-/// ===============================
+    let cfg = &ctx.accounts.collateral_config;
+    let price = collateral_price(ctx.remaining_accounts, cfg)?;
+    let collateral_value_6dp = token_amount_to_usd_6dp(
+        pos.collateral_amount,
+        ctx.accounts.collateral_mint.decimals,
+        price.liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode),
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    let healthy = is_above_maintenance(
+        collateral_value_6dp,
+        pos.debt_pusd as u128,
+        cfg.maintenance_ltv_bps,
+    );
+    require!(!healthy, ErrorCode::NotLiquidatable);
 
-#[derive(Accounts)]
-#[instruction(global_debt_ceiling: u64)]
-pub struct Initialize<'info> {
-    /// CHECK: PDA derived inside program; stored & used as signer for minting
-    #[account(
-        init,
-        payer = authority,
-        space = Protocol::LEN,
-        seeds = [Protocol::SEED_PREFIX, authority.key().as_ref()],
-        bump
-    )]
-    pub protocol: Account<'info, Protocol>,
+    // Price curve anchored at the oracle median
+    let (start_price_6dp, floor_price_6dp) = auction_curve_6dp(
+        price.price_6dp,
+        cfg.auction_start_premium_bps,
+        cfg.auction_floor_bps,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    require!(floor_price_6dp > 0, ErrorCode::PriceOutOfBounds);
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    // Penalty on the whole debt, booked as surplus and raised by the auction
+    let debt = pos.debt_pusd;
+    let penalty = liquidation_penalty_pusd(cfg, debt)?;
 
-    #[account(mut)]
-    pub pusd_mint: Account<'info, Mint>,
+    // Move debt off the position (stops stability fees) and onto the auction. Aggregate debt
+    // stays booked until keepers repay it or the shortfall is written off.
+    let cfg = &mut ctx.accounts.collateral_config;
+    decrease_position_debt(pos, cfg, debt)?;
+    if penalty > 0 {
+        book_fee(&mut ctx.accounts.protocol, cfg, penalty, FEE_SOURCE_LIQ_PENALTY)?;
+    }
+    let collateral_amount = pos.collateral_amount;
+    pos.collateral_amount = 0;
+    reindex_position(cfg, pos);
+    cfg.active_auctions = cfg
+        .active_auctions
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    /// System / SPL
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    let auction = &mut ctx.accounts.auction;
+    auction.collateral_config = cfg.key();
+    auction.position = pos.key();
+    auction.owner = pos.owner;
+    auction.collateral_amount = collateral_amount;
+    auction.debt_remaining_pusd = debt.checked_add(penalty).ok_or(ErrorCode::MathOverflow)?;
+    auction.start_price_6dp = start_price_6dp;
+    auction.floor_price_6dp = floor_price_6dp;
+    auction.start_ts = now_ts;
+    auction.duration_secs = cfg.auction_duration_secs;
+    auction.active = true;
+    auction.bump = *ctx.bumps.get("auction").ok_or(ErrorCode::InvalidPda)?;
+
+    emit!(AuctionStarted {
+        keeper: ctx.accounts.keeper.key(),
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        collateral_amount,
+        debt_target_pusd_6dp: auction.debt_remaining_pusd,
+        start_price_6dp,
+        floor_price_6dp,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct AddCollateral<'info> {
-    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
-    pub protocol: Account<'info, Protocol>,
-    pub authority: Signer<'info>,
+/// Permissionless: restart an auction whose price curve has run out, re-anchored at a fresh oracle
+/// price. The collateral for sale and the debt target carry over unchanged. Oracle accounts are
+/// passed as remaining accounts, in the order registered on the collateral config.
+pub fn handle_redo_auction(ctx: Context<RedoAuction>) -> Result<()> {
+    let cfg = &ctx.accounts.collateral_config;
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_LIQUIDATE)?;
 
-    /// Collateral mint we are registering
-    pub collateral_mint: Account<'info, Mint>,
+    let auction = &ctx.accounts.auction;
+    require!(auction.active, ErrorCode::AuctionNotActive);
+    let now_ts = Clock::get()?.unix_timestamp;
+    require!(
+        auction_expired(auction.start_ts, auction.duration_secs, now_ts),
+        ErrorCode::AuctionNotExpired
+    );
 
-    /// Collateral vault (SPL token account) owned by program PDA
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
+    let price = collateral_price(ctx.remaining_accounts, cfg)?;
+    let (start_price_6dp, floor_price_6dp) = auction_curve_6dp(
+        price.price_6dp,
+        cfg.auction_start_premium_bps,
+        cfg.auction_floor_bps,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    require!(floor_price_6dp > 0, ErrorCode::PriceOutOfBounds);
 
-    /// New PDA to store config for this collateral
-    #[account(
-        init,
-        payer = authority,
-        space = CollateralConfig::LEN,
-        seeds = [
-            CollateralConfig::SEED_PREFIX,
-            protocol.key().as_ref(),
-            collateral_mint.key().as_ref()
-        ],
-        bump
-    )]
-    pub collateral_config: Account<'info, CollateralConfig>,
+    let auction = &mut ctx.accounts.auction;
+    auction.start_price_6dp = start_price_6dp;
+    auction.floor_price_6dp = floor_price_6dp;
+    auction.start_ts = now_ts;
+    auction.duration_secs = cfg.auction_duration_secs;
 
-    pub system_program: Program<'info, System>,
+    emit!(AuctionRestarted {
+        keeper: ctx.accounts.keeper.key(),
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        collateral_amount: auction.collateral_amount,
+        debt_target_pusd_6dp: auction.debt_remaining_pusd,
+        start_price_6dp,
+        floor_price_6dp,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct OpenOrFundPosition<'info> {
+/// Buy up to `max_collateral` from a running auction at the current price, paying PUSD.
+/// Reverts if the current price is above `max_price_6dp`. Once the debt target is raised or the
+/// collateral runs out, leftover collateral returns to the position and any shortfall becomes bad debt.
+pub fn handle_take_auction(
+    ctx: Context<TakeAuction>,
+    max_collateral: u64,
+    max_price_6dp: u64,
+) -> Result<()> {
+    require!(max_collateral > 0, ErrorCode::ZeroAmount);
+
+    let auction = &ctx.accounts.auction;
+    require!(auction.active, ErrorCode::AuctionNotActive);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.collateral_config.paused_actions,
+        PAUSE_LIQUIDATE,
+    )?;
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    let price_6dp = dutch_auction_price_6dp(
+        auction.start_price_6dp as u128,
+        auction.floor_price_6dp as u128,
+        auction.start_ts,
+        auction.duration_secs,
+        now_ts,
+    );
+    require!(price_6dp > 0, ErrorCode::PriceOutOfBounds);
+    require!(price_6dp <= max_price_6dp as u128, ErrorCode::SlippageExceeded);
+
+    // Size the slice; the keeper never pays more than the remaining debt target
+    let unit = crate::state::ten_pow_u128(ctx.accounts.collateral_mint.decimals as u32);
+    let mut slice = max_collateral.min(auction.collateral_amount);
+    let cost = (slice as u128)
+        .checked_mul(price_6dp)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_add(unit - 1)
+        .ok_or(ErrorCode::MathOverflow)?
+        / unit;
+    let owe: u64 = if cost >= auction.debt_remaining_pusd as u128 {
+        let needed = (auction.debt_remaining_pusd as u128)
+            .checked_mul(unit)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_add(price_6dp - 1)
+            .ok_or(ErrorCode::MathOverflow)?
+            / price_6dp;
+        slice = slice.min(needed as u64);
+        auction.debt_remaining_pusd
+    } else {
+        cost as u64
+    };
+    require!(slice > 0 && owe > 0, ErrorCode::InvalidAmount);
+
+    // Keeper pays PUSD (burned against the debt)
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.keeper_pusd_ata.to_account_info(),
+        authority: ctx.accounts.keeper.to_account_info(),
+    };
+    let cpi_ctx_burn = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx_burn, owe)?;
+
+    // Vault releases the slice to the keeper
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.keeper_collateral_ata.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
+        slice,
+    )?;
+
+    // Update auction and aggregates
+    let cfg = &mut ctx.accounts.collateral_config;
+    let auction = &mut ctx.accounts.auction;
+    auction.collateral_amount = auction
+        .collateral_amount
+        .checked_sub(slice)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    auction.debt_remaining_pusd = auction
+        .debt_remaining_pusd
+        .checked_sub(owe)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, owe);
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(slice)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(AuctionTaken {
+        keeper: ctx.accounts.keeper.key(),
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        collateral_amount: slice,
+        paid_pusd_6dp: owe,
+        price_6dp: price_6dp as u64,
+        debt_remaining_pusd_6dp: auction.debt_remaining_pusd,
+    });
+
+    if auction.debt_remaining_pusd > 0 && auction.collateral_amount > 0 {
+        return Ok(());
+    }
+
+    // Settle: leftover collateral goes back to the borrower's position (still in the vault)
+    let returned = auction.collateral_amount;
+    let pos = &mut ctx.accounts.position;
+    pos.collateral_amount = pos
+        .collateral_amount
+        .checked_add(returned)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // Collateral ran out before the debt target was met: write the rest off as bad debt
+    let shortfall = auction.debt_remaining_pusd;
+    if shortfall > 0 {
+        reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, shortfall);
+        let protocol = &mut ctx.accounts.protocol;
+        protocol.bad_debt_pusd = protocol
+            .bad_debt_pusd
+            .checked_add(shortfall)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(BadDebtRecognized {
+            owner: auction.owner,
+            collateral_mint: cfg.collateral_mint,
+            bad_debt_pusd_6dp: shortfall,
+            total_bad_debt_pusd_6dp: protocol.bad_debt_pusd,
+        });
+    }
+
+    auction.collateral_amount = 0;
+    auction.debt_remaining_pusd = 0;
+    auction.active = false;
+    cfg.active_auctions = cfg
+        .active_auctions
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(AuctionSettled {
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        returned_collateral_amount: returned,
+        shortfall_pusd_6dp: shortfall,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: settle an auction still running at `cage` at the frozen price. The
+/// collateral covering its remaining debt target is set aside for PUSD holders and the rest
+/// returns to the position, for the owner to free.
+pub fn handle_settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    let auction = &ctx.accounts.auction;
+    require!(auction.active, ErrorCode::AuctionNotActive);
+    let cfg = &mut ctx.accounts.collateral_config;
+    require!(cfg.final_price_6dp > 0, ErrorCode::SettlementNotReady);
+
+    let debt = auction.debt_remaining_pusd;
+    let (owed, shortfall, returned) = settle_auction_at_final_price(
+        &mut ctx.accounts.protocol,
+        cfg,
+        &mut ctx.accounts.auction,
+        &mut ctx.accounts.position,
+        ctx.accounts.collateral_mint.decimals,
+    )?;
+
+    let auction = &ctx.accounts.auction;
+    emit!(PositionSettled {
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        debt_pusd_6dp: debt,
+        collateral_amount: owed,
+        shortfall_pusd_6dp: shortfall,
+    });
+    emit!(AuctionSettled {
+        owner: auction.owner,
+        collateral_mint: cfg.collateral_mint,
+        returned_collateral_amount: returned,
+        shortfall_pusd_6dp: shortfall,
+    });
+
+    Ok(())
+}
+
+/// Create the stability pool for a collateral type and its PUSD deposit account.
+pub fn handle_initialize_stability_pool(ctx: Context<InitializeStabilityPool>) -> Result<()> {
+    let pool = &mut ctx.accounts.stability_pool;
+
+    pool.collateral_config = ctx.accounts.collateral_config.key();
+    pool.pusd_account = ctx.accounts.stability_pool_pusd.key();
+    pool.total_deposits = 0;
+    pool.collateral_gains = 0;
+    pool.product_p = SP_PRECISION;
+    pool.sum_s = 0;
+    pool.sum_s_previous = 0;
+    pool.scale = 0;
+    pool.bump = *ctx.bumps.get("stability_pool").ok_or(ErrorCode::InvalidPda)?;
+
+    Ok(())
+}
+
+/// Deposit PUSD into a stability pool. Pending collateral gains are paid out first.
+pub fn handle_provide_to_stability_pool(
+    ctx: Context<ProvideToStabilityPool>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroAmount);
+
+    let deposit = &mut ctx.accounts.deposit;
+    if deposit.depositor == Pubkey::default() {
+        deposit.stability_pool = ctx.accounts.stability_pool.key();
+        deposit.depositor = ctx.accounts.depositor.key();
+        deposit.initial_deposit = 0;
+        deposit.bump = *ctx.bumps.get("deposit").ok_or(ErrorCode::InvalidPda)?;
+    }
+    let (compounded, gain) = settle_stability_deposit(&ctx.accounts.stability_pool, deposit)?;
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.depositor_pusd_ata.to_account_info(),
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.stability_pool_pusd.to_account_info(),
+        authority: ctx.accounts.depositor.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.pusd_mint.decimals)?;
+
+    if gain > 0 {
+        transfer_from_vault(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.collateral_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.depositor_collateral_ata.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            gain,
+        )?;
+    }
+
+    let pool = &mut ctx.accounts.stability_pool;
+    pool.total_deposits = pool.total_deposits.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    pay_stability_pool_gain(pool, &mut ctx.accounts.collateral_config, gain)?;
+
+    let deposit = &mut ctx.accounts.deposit;
+    let new_deposit = compounded.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    snapshot_stability_deposit(pool, deposit, new_deposit);
+
+    emit!(StabilityPoolDeposited {
+        depositor: deposit.depositor,
+        collateral_mint: ctx.accounts.collateral_config.collateral_mint,
+        amount_pusd_6dp: amount,
+        deposit_pusd_6dp: new_deposit,
+        collateral_gain_paid: gain,
+    });
+
+    Ok(())
+}
+
+/// Withdraw up to `amount` PUSD from a stability pool and claim collateral gains.
+/// `amount = 0` only claims gains.
+pub fn handle_withdraw_from_stability_pool(
+    ctx: Context<WithdrawFromStabilityPool>,
+    amount: u64,
+) -> Result<()> {
+    let (compounded, gain) =
+        settle_stability_deposit(&ctx.accounts.stability_pool, &ctx.accounts.deposit)?;
+    let withdraw = amount.min(compounded);
+    require!(withdraw > 0 || gain > 0, ErrorCode::ZeroAmount);
+
+    if withdraw > 0 {
+        let pool = &ctx.accounts.stability_pool;
+        let seeds = [
+            StabilityPool::SEED_PREFIX,
+            pool.collateral_config.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.stability_pool_pusd.to_account_info(),
+            mint: ctx.accounts.pusd_mint.to_account_info(),
+            to: ctx.accounts.depositor_pusd_ata.to_account_info(),
+            authority: ctx.accounts.stability_pool.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, withdraw, ctx.accounts.pusd_mint.decimals)?;
+    }
+
+    if gain > 0 {
+        transfer_from_vault(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.collateral_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.depositor_collateral_ata.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            gain,
+        )?;
+    }
+
+    // Compounded deposits round down, so their sum never exceeds the pool total
+    let pool = &mut ctx.accounts.stability_pool;
+    pool.total_deposits = pool.total_deposits.saturating_sub(withdraw);
+    pay_stability_pool_gain(pool, &mut ctx.accounts.collateral_config, gain)?;
+
+    let deposit = &mut ctx.accounts.deposit;
+    let new_deposit = compounded - withdraw;
+    snapshot_stability_deposit(pool, deposit, new_deposit);
+
+    emit!(StabilityPoolWithdrawn {
+        depositor: deposit.depositor,
+        collateral_mint: ctx.accounts.collateral_config.collateral_mint,
+        amount_pusd_6dp: withdraw,
+        deposit_pusd_6dp: new_deposit,
+        collateral_gain_paid: gain,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: liquidate an unhealthy position against the stability pool.
+/// Pool PUSD is burned to cancel the debt and the seized collateral (bonus included) is shared
+/// pro-rata among depositors. Repayment follows the same close-factor/target-health limits as
+/// `handle_liquidate`, further bounded by what the pool can absorb.
+pub fn handle_liquidate_with_stability_pool(ctx: Context<LiquidateWithStabilityPool>) -> Result<()> {
+    let cfg = &mut ctx.accounts.collateral_config;
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_LIQUIDATE)?;
+    let pos = &mut ctx.accounts.position;
+    require!(pos.collateral_config == cfg.key(), ErrorCode::UnsupportedCollateral);
+
+    // Realize stability fees so eligibility is judged on the full debt
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_position_debt(pos, cfg, now_ts)?;
+    let cfg = &ctx.accounts.collateral_config;
+
+    let collateral_price_usd_6dp = collateral_price(ctx.remaining_accounts, cfg)?
+        .liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode);
+    let collateral_decimals = ctx.accounts.collateral_mint.decimals;
+    let collateral_value_6dp = token_amount_to_usd_6dp(
+        pos.collateral_amount,
+        collateral_decimals,
+        collateral_price_usd_6dp,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    let healthy = is_above_maintenance(
+        collateral_value_6dp,
+        pos.debt_pusd as u128,
+        cfg.maintenance_ltv_bps,
+    );
+    require!(!healthy, ErrorCode::NotLiquidatable);
+
+    // Size the repayment: liquidation limits, then what the pool can absorb
+    let (max_repay, full_liquidation) = max_liquidation_repay(cfg, collateral_value_6dp, pos.debt_pusd)?;
+    let requested = if full_liquidation {
+        pos.debt_pusd
+            .checked_add(liquidation_penalty_pusd(cfg, pos.debt_pusd)?)
+            .ok_or(ErrorCode::MathOverflow)?
+    } else {
+        max_repay
+    };
+    let available = ctx
+        .accounts
+        .stability_pool
+        .total_deposits
+        .saturating_sub(MIN_STABILITY_POOL_DEPOSITS);
+    let requested = requested.min(available);
+    require!(requested > 0, ErrorCode::StabilityPoolEmpty);
+
+    let penalty = liquidation_penalty_pusd(cfg, requested.min(pos.debt_pusd))?;
+    if penalty > 0 {
+        let cfg = &mut ctx.accounts.collateral_config;
+        increase_position_debt(pos, cfg, penalty)?;
+        book_fee(&mut ctx.accounts.protocol, cfg, penalty, FEE_SOURCE_LIQ_PENALTY)?;
+    }
+    let cfg = &ctx.accounts.collateral_config;
+    let repay = requested.min(pos.debt_pusd);
+
+    let seize_amount = seize_for_repay(
+        repay,
+        collateral_decimals,
+        collateral_price_usd_6dp,
+        cfg.liq_bonus_bps,
+    )?
+    .min(pos.collateral_amount);
+    require!(seize_amount > 0, ErrorCode::InvalidAmount);
+
+    // Burn pool PUSD against the debt; the seized collateral stays in the vault for depositors
+    let pool = &ctx.accounts.stability_pool;
+    let seeds = [
+        StabilityPool::SEED_PREFIX,
+        pool.collateral_config.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.stability_pool_pusd.to_account_info(),
+        authority: ctx.accounts.stability_pool.to_account_info(),
+    };
+    let cpi_ctx_burn =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_burn, signer);
+    token_interface::burn(cpi_ctx_burn, repay)?;
+
+    // Update position and aggregates
+    pos.collateral_amount = pos
+        .collateral_amount
+        .checked_sub(seize_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    let cfg = &mut ctx.accounts.collateral_config;
+    decrease_position_debt(pos, cfg, repay)?;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, repay);
+
+    // Distribute: deposits shrink by `repay`, gains grow by `seize_amount`
+    let pool = &mut ctx.accounts.stability_pool;
+    let mut acc = stability_pool_accumulators(pool);
+    acc.offset(pool.total_deposits, repay, seize_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.product_p = acc.product_p;
+    pool.sum_s = acc.sum_s;
+    pool.sum_s_previous = acc.sum_s_previous;
+    pool.scale = acc.scale;
+    pool.total_deposits = pool
+        .total_deposits
+        .checked_sub(repay)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    pool.collateral_gains = pool
+        .collateral_gains
+        .checked_add(seize_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    recognize_bad_debt(&mut ctx.accounts.protocol, cfg, pos)?;
+    reindex_position(cfg, pos);
+
+    emit!(StabilityPoolOffset {
+        owner: pos.owner,
+        collateral_mint: cfg.collateral_mint,
+        debt_offset_pusd_6dp: repay,
+        collateral_gained: seize_amount,
+        total_deposits_pusd_6dp: pool.total_deposits,
+    });
+
+    Ok(())
+}
+
+/// Approve a stablecoin for the PSM and create its vault.
+pub fn handle_add_psm_asset(
+    ctx: Context<AddPsmAsset>,
+    fee_in_bps: u16,
+    fee_out_bps: u16,
+    debt_ceiling: u64,
+) -> Result<()> {
+    require!(fee_in_bps <= MAX_PSM_FEE_BPS, ErrorCode::InvalidParameter);
+    require!(fee_out_bps <= MAX_PSM_FEE_BPS, ErrorCode::InvalidParameter);
+    // The PSM vault is held to the same Token-2022 extension rules as collateral vaults
+    let stable_mint_info = ctx.accounts.stable_mint.to_account_info();
+    require!(
+        collateral_mint_extensions_safe(&stable_mint_info.try_borrow_data()?),
+        ErrorCode::UnsupportedMintExtension
+    );
+
+    let psm = &mut ctx.accounts.psm_config;
+    psm.protocol = ctx.accounts.protocol.key();
+    psm.stable_mint = ctx.accounts.stable_mint.key();
+    psm.vault = ctx.accounts.psm_vault.key();
+    psm.fee_in_bps = fee_in_bps;
+    psm.fee_out_bps = fee_out_bps;
+    psm.debt_ceiling = debt_ceiling;
+    psm.total_minted_pusd = 0;
+    // Under the timelock a new asset starts inactive; activating it is a governance change
+    psm.active = ctx.accounts.protocol.governance == Pubkey::default();
+    psm.bump = *ctx.bumps.get("psm_config").ok_or(ErrorCode::InvalidPda)?;
+
+    emit!(PsmAssetAdded {
+        protocol: psm.protocol,
+        stable_mint: psm.stable_mint,
+        vault: psm.vault,
+        fee_in_bps,
+        fee_out_bps,
+        debt_ceiling,
+    });
+
+    Ok(())
+}
+
+/// Swap an approved stablecoin for PUSD 1:1, less `fee_in_bps` (minted to the treasury).
+pub fn handle_psm_swap_in(ctx: Context<PsmSwapIn>, stable_amount: u64) -> Result<()> {
+    require!(stable_amount > 0, ErrorCode::ZeroAmount);
+    require_not_paused(&ctx.accounts.protocol, 0, PAUSE_PSM)?;
+
+    require!(ctx.accounts.psm_config.active, ErrorCode::CollateralInactive);
+
+    // Stablecoin in. A transfer-fee mint delivers less than `stable_amount`, so PUSD is minted
+    // against what the vault actually received.
+    let vault_before = ctx.accounts.psm_vault.amount;
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.user_stable_ata.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        to: ctx.accounts.psm_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new(ctx.accounts.stable_token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, stable_amount, ctx.accounts.stable_mint.decimals)?;
+    ctx.accounts.psm_vault.reload()?;
+    let received = ctx
+        .accounts
+        .psm_vault
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(received > 0, ErrorCode::ZeroAmount);
+
+    let psm = &ctx.accounts.psm_config;
+    let gross = convert_decimals(
+        received,
+        ctx.accounts.stable_mint.decimals,
+        PUSD_DECIMALS as u8,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    require!(gross > 0, ErrorCode::InvalidAmount);
+    let fee = ((gross as u128) * psm.fee_in_bps as u128 / BPS_DENOMINATOR) as u64;
+    let net = gross - fee;
+
+    // PSM and global debt ceilings
+    let new_psm_minted = debt_after_mint(psm.total_minted_pusd, gross, psm.debt_ceiling)
+        .ok_or(ErrorCode::PsmCeilingReached)?;
+
+    let protocol = &ctx.accounts.protocol;
+    let new_total_debt = debt_after_mint(
+        protocol.total_debt_pusd,
+        gross,
+        protocol.global_debt_ceiling,
+    )
+    .ok_or(ErrorCode::GlobalDebtCeilingReached)?;
+
+    // PUSD out (Protocol PDA is the mint authority); the fee is minted to the treasury
+    let seeds = [
+        Protocol::SEED_PREFIX,
+        protocol.seed_key.as_ref(),
+        &[protocol.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::MintTo {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.user_pusd_ata.to_account_info(),
+        authority: ctx.accounts.protocol.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::mint_to(cpi_ctx, net)?;
+
+    if fee > 0 {
+        let cpi_accounts = token_interface::MintTo {
+            mint: ctx.accounts.pusd_mint.to_account_info(),
+            to: ctx.accounts.treasury_pusd.to_account_info(),
+            authority: ctx.accounts.protocol.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token_interface::mint_to(cpi_ctx, fee)?;
+    }
+
+    ctx.accounts.psm_config.total_minted_pusd = new_psm_minted;
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.total_debt_pusd = new_total_debt;
+    if fee > 0 {
+        book_realized_fee(protocol, fee, FEE_SOURCE_PSM)?;
+    }
+
+    emit!(PsmSwappedIn {
+        user: ctx.accounts.user.key(),
+        stable_mint: ctx.accounts.psm_config.stable_mint,
+        stable_amount: received,
+        pusd_out_6dp: net,
+        fee_pusd_6dp: fee,
+    });
+
+    Ok(())
+}
+
+/// Swap PUSD for an approved stablecoin 1:1, less `fee_out_bps` (sent to the treasury).
+pub fn handle_psm_swap_out(ctx: Context<PsmSwapOut>, pusd_amount: u64) -> Result<()> {
+    require!(pusd_amount > 0, ErrorCode::ZeroAmount);
+    // PSM exits stay open through settlement until `thaw` fixes the PUSD supply
+    let protocol = &ctx.accounts.protocol;
+    require!(
+        protocol.settlement_phase != SettlementPhase::Thawed,
+        ErrorCode::ProtocolCaged
+    );
+    require_action_not_paused(protocol, 0, PAUSE_PSM)?;
+
+    let psm = &ctx.accounts.psm_config;
+    let fee = ((pusd_amount as u128) * psm.fee_out_bps as u128 / BPS_DENOMINATOR) as u64;
+    let net = pusd_amount - fee;
+
+    let stable_amount = convert_decimals(
+        net,
+        PUSD_DECIMALS as u8,
+        ctx.accounts.stable_mint.decimals,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    require!(stable_amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        stable_amount <= ctx.accounts.psm_vault.amount,
+        ErrorCode::PsmInsufficientLiquidity
+    );
+
+    // Fee to the treasury, the rest is burned
+    if fee > 0 {
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.user_pusd_ata.to_account_info(),
+            mint: ctx.accounts.pusd_mint.to_account_info(),
+            to: ctx.accounts.treasury_pusd.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, fee, ctx.accounts.pusd_mint.decimals)?;
+    }
+
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.user_pusd_ata.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx, net)?;
+
+    // Stablecoin out of the PSM vault
+    let seeds = [
+        PsmConfig::SEED_PREFIX,
+        psm.protocol.as_ref(),
+        psm.stable_mint.as_ref(),
+        &[psm.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.psm_vault.to_account_info(),
+        mint: ctx.accounts.stable_mint.to_account_info(),
+        to: ctx.accounts.user_stable_ata.to_account_info(),
+        authority: ctx.accounts.psm_config.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.stable_token_program.to_account_info(),
+        cpi_accounts,
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, stable_amount, ctx.accounts.stable_mint.decimals)?;
+
+    // Decimal rounding can leave the PSM slightly over-backed; saturate instead of failing
+    let psm = &mut ctx.accounts.psm_config;
+    psm.total_minted_pusd = psm.total_minted_pusd.saturating_sub(net);
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.total_debt_pusd = protocol.total_debt_pusd.saturating_sub(net);
+    if fee > 0 {
+        book_realized_fee(protocol, fee, FEE_SOURCE_PSM)?;
+    }
+
+    emit!(PsmSwappedOut {
+        user: ctx.accounts.user.key(),
+        stable_mint: psm.stable_mint,
+        pusd_in_6dp: pusd_amount,
+        stable_amount,
+        fee_pusd_6dp: fee,
+    });
+
+    Ok(())
+}
+
+/// Redeem PUSD for collateral at face value, less the redemption fee.
+/// Remaining accounts are the collateral's oracles (in registered order) followed by the
+/// positions to redeem against. Each position must be in the riskiest non-empty redemption
+/// bucket when it is reached, no healthier than the one before it, and at or above
+/// maintenance; the fee stays with the redeemed positions as collateral.
+pub fn handle_redeem(ctx: Context<Redeem>, pusd_amount: u64, max_fee_bps: u16) -> Result<()> {
+    require!(pusd_amount > 0, ErrorCode::ZeroAmount);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.collateral_config.paused_actions,
+        PAUSE_WITHDRAW,
+    )?;
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    let cfg = &mut ctx.accounts.collateral_config;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+
+    // Fee from the decayed base rate plus this redemption's share of supply
+    let supply = ctx.accounts.pusd_mint.supply;
+    let decayed_base_rate = decay_redemption_base_rate(
+        cfg.redemption_base_rate,
+        now_ts.saturating_sub(cfg.last_redemption_ts),
+    );
+    let fee_bps = redemption_fee_bps(
+        cfg.redemption_fee_floor_bps,
+        bump_redemption_base_rate(decayed_base_rate, pusd_amount, supply),
+    );
+    require!(fee_bps <= max_fee_bps, ErrorCode::SlippageExceeded);
+    require!((fee_bps as u128) < BPS_DENOMINATOR, ErrorCode::InvalidAmount);
+
+    // Redeemers are paid at the top of the confidence band so they never get more than $1
+    let price_account_count = cfg.price_account_count();
+    require!(
+        ctx.remaining_accounts.len() > price_account_count,
+        ErrorCode::RedemptionTargetInvalid
+    );
+    let (oracles, positions) = ctx.remaining_accounts.split_at(price_account_count);
+    let price = collateral_price(oracles, cfg)?;
+    let price_6dp =
+        price.liquidation_price_6dp(cfg.conf_multiplier_bps, LiquidationPriceMode::UpperBound);
+    // Eligibility uses the liquidation price, so redeemers and liquidators never share a target
+    let health_price_6dp =
+        price.liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode);
+    let collateral_decimals = ctx.accounts.collateral_mint.decimals;
+
+    let mut remaining = pusd_amount;
+    let mut previous_nominal_health: u128 = 0;
+    let mut redeemed: u64 = 0;
+    let mut collateral_out: u64 = 0;
+    let mut positions_touched: u8 = 0;
+    for info in positions.iter() {
+        if remaining == 0 {
+            break;
+        }
+        require!(
+            info.owner == &crate::ID && info.is_writable,
+            ErrorCode::RedemptionTargetInvalid
+        );
+        let mut pos = {
+            let data = info.try_borrow_data()?;
+            Position::try_deserialize(&mut &data[..])?
+        };
+        require!(pos.collateral_config == cfg.key(), ErrorCode::UnsupportedCollateral);
+        let nominal_health = compute_health_bps(pos.collateral_amount as u128, pos.normalized_debt);
+        require!(
+            redemption_order_ok(
+                pos.redemption_bucket,
+                lowest_redemption_bucket(cfg),
+                nominal_health,
+                previous_nominal_health,
+            ),
+            ErrorCode::RedemptionOrderViolated
+        );
+        previous_nominal_health = nominal_health;
+        sync_position_debt(&mut pos, cfg, now_ts)?;
+
+        // Positions below maintenance are for liquidators, not redeemers
+        let collateral_value_6dp =
+            token_amount_to_usd_6dp(pos.collateral_amount, collateral_decimals, health_price_6dp)
+                .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            is_above_maintenance(
+                collateral_value_6dp,
+                pos.debt_pusd as u128,
+                cfg.maintenance_ltv_bps,
+            ),
+            ErrorCode::RedemptionTargetInvalid
+        );
+
+        let debt = remaining.min(pos.debt_pusd);
+        let net = (debt as u128)
+            .checked_mul(BPS_DENOMINATOR - fee_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / BPS_DENOMINATOR;
+        let collateral = seize_for_repay(net as u64, collateral_decimals, price_6dp, 0)?;
+        require!(
+            collateral <= pos.collateral_amount,
+            ErrorCode::RedemptionTargetInvalid
+        );
+
+        pos.collateral_amount -= collateral;
+        decrease_position_debt(&mut pos, cfg, debt)?;
+        reindex_position(cfg, &mut pos);
+        {
+            let mut data = info.try_borrow_mut_data()?;
+            pos.try_serialize(&mut &mut data[..])?;
+        }
+
+        remaining -= debt;
+        redeemed = redeemed.checked_add(debt).ok_or(ErrorCode::MathOverflow)?;
+        collateral_out = collateral_out
+            .checked_add(collateral)
+            .ok_or(ErrorCode::MathOverflow)?;
+        positions_touched = positions_touched.saturating_add(1);
+    }
+    require!(redeemed > 0 && collateral_out > 0, ErrorCode::InvalidAmount);
+
+    // Burn the redeemed PUSD and release the collateral
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.redeemer_pusd_ata.to_account_info(),
+        authority: ctx.accounts.redeemer.to_account_info(),
+    };
+    let cpi_ctx_burn = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx_burn, redeemed)?;
+
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.redeemer_collateral_ata.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
+        collateral_out,
+    )?;
+
+    // Update aggregates and the base rate (charged on what was actually redeemed)
+    let cfg = &mut ctx.accounts.collateral_config;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, redeemed);
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(collateral_out)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    cfg.redemption_base_rate = bump_redemption_base_rate(decayed_base_rate, redeemed, supply);
+    cfg.last_redemption_ts = now_ts;
+
+    emit!(Redeemed {
+        redeemer: ctx.accounts.redeemer.key(),
+        collateral_mint: cfg.collateral_mint,
+        redeemed_pusd_6dp: redeemed,
+        collateral_amount: collateral_out,
+        fee_bps,
+        positions_touched,
+        base_rate: cfg.redemption_base_rate,
+    });
+
+    Ok(())
+}
+
+/// Root admin: assign a delegated role to a new key.
+pub fn handle_set_role(ctx: Context<SetRole>, role: ProtocolRole, new_holder: Pubkey) -> Result<()> {
+    require!(new_holder != Pubkey::default(), ErrorCode::InvalidParameter);
+
+    let protocol = &mut ctx.accounts.protocol;
+    let old_holder = protocol.set_role_holder(role, new_holder);
+
+    emit!(RoleUpdated {
+        protocol: protocol.key(),
+        role,
+        old_holder,
+        new_holder,
+    });
+
+    Ok(())
+}
+
+/// Root admin: nominate a new root admin. Takes effect once the nominee calls
+/// `accept_authority`; proposing `Pubkey::default()` withdraws a pending nomination.
+pub fn handle_propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    // Authority-seeded deployments must run `migrate_protocol` first, or the PDA would
+    // stop signing once the authority changes
+    require!(protocol.seed_key != Pubkey::default(), ErrorCode::InvalidPda);
+    require!(new_authority != protocol.authority, ErrorCode::InvalidParameter);
+
+    protocol.pending_authority = new_authority;
+
+    emit!(AuthorityProposed {
+        protocol: protocol.key(),
+        authority: protocol.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+/// Pending admin: accept a nomination and become the root admin.
+/// Delegated roles are left as they are; the new admin reassigns them with `set_role`.
+pub fn handle_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    let old_authority = protocol
+        .accept_pending_authority()
+        .ok_or(ErrorCode::Unauthorized)?;
+
+    emit!(AuthorityTransferred {
+        protocol: protocol.key(),
+        old_authority,
+        new_authority: protocol.authority,
+    });
+
+    Ok(())
+}
+
+/// Root admin: move a Protocol created under the legacy ["protocol", authority] seed onto
+/// the current layout. The PDA address, and so the PUSD mint authority, is unchanged; the
+/// original authority is pinned as `seed_key` so the admin itself can then be rotated.
+pub fn handle_migrate_protocol(ctx: Context<MigrateProtocol>) -> Result<()> {
+    let protocol_info = ctx.accounts.protocol.to_account_info();
+    require_keys_eq!(*protocol_info.owner, crate::ID, ErrorCode::InvalidPda);
+
+    // Decode the baseline layout explicitly; an account already on the current layout
+    // has the same discriminator but a different size
+    let migrated = {
+        let data = protocol_info.try_borrow_data()?;
+        require!(data.len() >= 8, ErrorCode::InvalidPda);
+        require!(data[..8] == Protocol::DISCRIMINATOR, ErrorCode::InvalidPda);
+        require!(
+            data.len() == Protocol::BASELINE_LEN,
+            ErrorCode::ProtocolAlreadyMigrated
+        );
+        Protocol::from_baseline(&data).ok_or(ErrorCode::InvalidPda)?
+    };
+    let authority = migrated.authority;
+    let bump = migrated.bump;
+    require_keys_eq!(authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+    let expected = Pubkey::create_program_address(
+        &[Protocol::SEED_PREFIX, authority.as_ref(), &[bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidPda)?;
+    require_keys_eq!(expected, protocol_info.key(), ErrorCode::InvalidPda);
+
+    // Top up rent for the larger account, then grow it (new bytes are zeroed)
+    let rent_due = Rent::get()?
+        .minimum_balance(Protocol::LEN)
+        .saturating_sub(protocol_info.lamports());
+    if rent_due > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: protocol_info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    protocol_info.realloc(Protocol::LEN, true)?;
+
+    migrated.try_serialize(&mut &mut protocol_info.try_borrow_mut_data()?[..])?;
+
+    emit!(ProtocolMigrated {
+        protocol: protocol_info.key(),
+        seed_key: authority,
+    });
+
+    Ok(())
+}
+
+/// Guardian: pause/unpause the given PAUSE_* actions protocol-wide (emergency circuit breaker).
+pub fn handle_toggle_pause(ctx: Context<TogglePause>, actions: u8, paused: bool) -> Result<()> {
+    require!(
+        actions != 0 && actions & !PAUSE_ALL == 0,
+        ErrorCode::InvalidParameter
+    );
+
+    let protocol = &mut ctx.accounts.protocol;
+    let (paused_actions, changed_actions) = apply_pause(protocol.paused_actions, actions, paused);
+    protocol.paused_actions = paused_actions;
+
+    emit!(PauseToggled {
+        protocol: protocol.key(),
+        collateral_config: None,
+        paused,
+        changed_actions,
+        paused_actions,
+    });
+
+    Ok(())
+}
+
+/// Guardian: pause/unpause the given PAUSE_* actions for one collateral type.
+pub fn handle_toggle_collateral_pause(
+    ctx: Context<ToggleCollateralPause>,
+    actions: u8,
+    paused: bool,
+) -> Result<()> {
+    require!(
+        actions != 0 && actions & !PAUSE_COLLATERAL_ACTIONS == 0,
+        ErrorCode::InvalidParameter
+    );
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    let (paused_actions, changed_actions) = apply_pause(cfg.paused_actions, actions, paused);
+    cfg.paused_actions = paused_actions;
+
+    emit!(PauseToggled {
+        protocol: ctx.accounts.protocol.key(),
+        collateral_config: Some(cfg.key()),
+        paused,
+        changed_actions,
+        paused_actions,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: advance a collateral's stability-fee rate index to now.
+/// Positions pick up the new index lazily the next time they are touched.
+pub fn handle_accrue(ctx: Context<Accrue>) -> Result<()> {
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(
+        &mut ctx.accounts.protocol,
+        &mut ctx.accounts.collateral_config,
+        now_ts,
+    )?;
+    Ok(())
+}
+
+/// Create the treasury PDA and its PUSD token account, and set the sweep destination.
+pub fn handle_initialize_treasury(
+    ctx: Context<InitializeTreasury>,
+    surplus_buffer_pusd: u64,
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+
+    treasury.protocol = ctx.accounts.protocol.key();
+    treasury.pusd_account = ctx.accounts.treasury_pusd.key();
+    treasury.surplus_destination = ctx.accounts.surplus_destination.key();
+    treasury.surplus_buffer_pusd = surplus_buffer_pusd;
+    treasury.total_swept_pusd = 0;
+    treasury.bump = *ctx.bumps.get("treasury").ok_or(ErrorCode::InvalidPda)?;
+
+    emit!(TreasuryInitialized {
+        protocol: treasury.protocol,
+        treasury: treasury.key(),
+        pusd_account: treasury.pusd_account,
+        surplus_destination: treasury.surplus_destination,
+        surplus_buffer_pusd_6dp: surplus_buffer_pusd,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: mint the protocol's accrued surplus into the treasury PUSD account.
+/// Accrued fees are already counted in aggregate debt, so minting them keeps PUSD supply
+/// equal to outstanding debt.
+pub fn handle_collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+    let protocol = &ctx.accounts.protocol;
+    let (amount, realized_fees) =
+        realize_accrued_surplus(protocol.accrued_surplus_pusd, protocol.realized_fees_pusd)
+            .ok_or(ErrorCode::MathOverflow)?;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = [
+        Protocol::SEED_PREFIX,
+        protocol.seed_key.as_ref(),
+        &[protocol.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::MintTo {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.treasury_pusd.to_account_info(),
+        authority: ctx.accounts.protocol.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::mint_to(cpi_ctx, amount)?;
+
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.accrued_surplus_pusd = 0;
+    protocol.realized_fees_pusd = realized_fees;
+
+    emit!(FeeBooked {
+        protocol: protocol.key(),
+        source: pack_label(FEE_SOURCE_REALIZED),
+        amount_pusd_6dp: amount,
+        accrued_surplus_pusd_6dp: protocol.accrued_surplus_pusd,
+        realized_fees_pusd_6dp: protocol.realized_fees_pusd,
+    });
+
+    Ok(())
+}
+
+/// Treasury role: sweep treasury PUSD above the surplus buffer to the configured destination.
+pub fn handle_sweep_surplus(ctx: Context<SweepSurplus>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::ZeroAmount);
+
+    let treasury = &ctx.accounts.treasury;
+    let available = sweepable_surplus(ctx.accounts.treasury_pusd.amount, treasury.surplus_buffer_pusd);
+    require!(amount <= available, ErrorCode::InsufficientSurplus);
+
+    let seeds = [
+        Treasury::SEED_PREFIX,
+        treasury.protocol.as_ref(),
+        &[treasury.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.treasury_pusd.to_account_info(),
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.surplus_destination.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.pusd_mint.decimals)?;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_swept_pusd = treasury
+        .total_swept_pusd
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(SurplusSwept {
+        protocol: treasury.protocol,
+        destination: treasury.surplus_destination,
+        amount_pusd_6dp: amount,
+        total_swept_pusd_6dp: treasury.total_swept_pusd,
+    });
+
+    Ok(())
+}
+
+/// Treasury role: write off up to `max_amount` of bad debt against protocol surplus.
+/// Accrued (unminted) surplus is netted first; the rest is burned from the treasury,
+/// which may dip into the surplus buffer. Anything left stays socialized across PUSD holders.
+pub fn handle_cover_bad_debt(ctx: Context<CoverBadDebt>, max_amount: u64) -> Result<()> {
+    let protocol = &ctx.accounts.protocol;
+    require!(max_amount.min(protocol.bad_debt_pusd) > 0, ErrorCode::ZeroAmount);
+
+    // Accrued fees were never minted, so cancelling them against bad debt needs no tokens
+    let (from_accrued, from_treasury) = bad_debt_cover_split(
+        protocol.bad_debt_pusd,
+        max_amount,
+        protocol.accrued_surplus_pusd,
+        ctx.accounts.treasury_pusd.amount,
+    );
+    require!(from_accrued + from_treasury > 0, ErrorCode::InsufficientSurplus);
+
+    if from_treasury > 0 {
+        let treasury = &ctx.accounts.treasury;
+        let seeds = [
+            Treasury::SEED_PREFIX,
+            treasury.protocol.as_ref(),
+            &[treasury.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token_interface::Burn {
+            mint: ctx.accounts.pusd_mint.to_account_info(),
+            from: ctx.accounts.treasury_pusd.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token_interface::burn(cpi_ctx, from_treasury)?;
+    }
+
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.accrued_surplus_pusd -= from_accrued;
+    protocol.bad_debt_pusd -= from_accrued + from_treasury;
+
+    emit!(BadDebtCovered {
+        protocol: protocol.key(),
+        from_accrued_surplus_pusd_6dp: from_accrued,
+        burned_from_treasury_pusd_6dp: from_treasury,
+        remaining_bad_debt_pusd_6dp: protocol.bad_debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Governance: create the sPUSD savings vault, its share mint and PUSD account.
+pub fn handle_initialize_savings(ctx: Context<InitializeSavings>, savings_rate_bps: u16) -> Result<()> {
+    require!(savings_rate_bps <= MAX_SAVINGS_RATE_BPS, ErrorCode::InvalidParameter);
+
+    let vault = &mut ctx.accounts.savings_vault;
+    vault.protocol = ctx.accounts.protocol.key();
+    vault.share_mint = ctx.accounts.share_mint.key();
+    vault.pusd_account = ctx.accounts.savings_pusd.key();
+    vault.savings_rate_bps = savings_rate_bps;
+    vault.savings_index = RATE_INDEX_ONE;
+    vault.last_accrual_ts = Clock::get()?.unix_timestamp;
+    vault.total_interest_pusd = 0;
+    vault.bump = *ctx.bumps.get("savings_vault").ok_or(ErrorCode::InvalidPda)?;
+
+    emit!(SavingsInitialized {
+        protocol: vault.protocol,
+        share_mint: vault.share_mint,
+        pusd_account: vault.pusd_account,
+        savings_rate_bps,
+    });
+
+    Ok(())
+}
+
+/// Deposit PUSD into the savings vault for sPUSD at the current savings index.
+pub fn handle_savings_deposit(ctx: Context<SavingsDeposit>, pusd_amount: u64) -> Result<()> {
+    require!(pusd_amount > 0, ErrorCode::ZeroAmount);
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_savings(
+        &mut ctx.accounts.savings_vault,
+        ctx.accounts.share_mint.supply,
+        &ctx.accounts.treasury,
+        &ctx.accounts.treasury_pusd,
+        &ctx.accounts.pusd_mint,
+        ctx.accounts.savings_pusd.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        now_ts,
+    )?;
+
+    let vault = &ctx.accounts.savings_vault;
+    let shares = savings_shares_for_pusd(pusd_amount, vault.savings_index)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(shares > 0, ErrorCode::InvalidAmount);
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.user_pusd_ata.to_account_info(),
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.savings_pusd.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, pusd_amount, ctx.accounts.pusd_mint.decimals)?;
+
+    let seeds = [
+        SavingsVault::SEED_PREFIX,
+        vault.protocol.as_ref(),
+        &[vault.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::MintTo {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        to: ctx.accounts.user_share_ata.to_account_info(),
+        authority: ctx.accounts.savings_vault.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::mint_to(cpi_ctx, shares)?;
+
+    emit!(SavingsDeposited {
+        owner: ctx.accounts.owner.key(),
+        pusd_amount_6dp: pusd_amount,
+        shares,
+        savings_index: vault.savings_index,
+    });
+
+    Ok(())
+}
+
+/// Burn sPUSD and withdraw the PUSD it is worth at the current savings index.
+pub fn handle_savings_withdraw(ctx: Context<SavingsWithdraw>, shares: u64) -> Result<()> {
+    require!(shares > 0, ErrorCode::ZeroAmount);
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_savings(
+        &mut ctx.accounts.savings_vault,
+        ctx.accounts.share_mint.supply,
+        &ctx.accounts.treasury,
+        &ctx.accounts.treasury_pusd,
+        &ctx.accounts.pusd_mint,
+        ctx.accounts.savings_pusd.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        now_ts,
+    )?;
+
+    let vault = &ctx.accounts.savings_vault;
+    let pusd_amount = savings_pusd_for_shares(shares, vault.savings_index)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(pusd_amount > 0, ErrorCode::InvalidAmount);
+
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.share_mint.to_account_info(),
+        from: ctx.accounts.user_share_ata.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx, shares)?;
+
+    let seeds = [
+        SavingsVault::SEED_PREFIX,
+        vault.protocol.as_ref(),
+        &[vault.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.savings_pusd.to_account_info(),
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.user_pusd_ata.to_account_info(),
+        authority: ctx.accounts.savings_vault.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, pusd_amount, ctx.accounts.pusd_mint.decimals)?;
+
+    emit!(SavingsWithdrawn {
+        owner: ctx.accounts.owner.key(),
+        pusd_amount_6dp: pusd_amount,
+        shares,
+        savings_index: vault.savings_index,
+    });
+
+    Ok(())
+}
+
+/// Read-only: sPUSD a deposit of `pusd_amount` would mint right now.
+pub fn handle_preview_savings_deposit(ctx: Context<PreviewSavings>, pusd_amount: u64) -> Result<u64> {
+    let savings_index = preview_savings_index(&ctx)?;
+    let shares = savings_shares_for_pusd(pusd_amount, savings_index).ok_or(ErrorCode::MathOverflow)?;
+    Ok(shares)
+}
+
+/// Read-only: PUSD that withdrawing `shares` sPUSD would pay out right now.
+pub fn handle_preview_savings_withdraw(ctx: Context<PreviewSavings>, shares: u64) -> Result<u64> {
+    let savings_index = preview_savings_index(&ctx)?;
+    let pusd_amount = savings_pusd_for_shares(shares, savings_index).ok_or(ErrorCode::MathOverflow)?;
+    Ok(pusd_amount)
+}
+
+/// Enable the governance timelock. Risk parameters only change through `queue_change` /
+/// `execute_change`, so none can change before this runs.
+pub fn handle_initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    timelock_secs: u64,
+) -> Result<()> {
+    require!(
+        (MIN_TIMELOCK_SECS..=MAX_TIMELOCK_SECS).contains(&timelock_secs),
+        ErrorCode::InvalidParameter
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    governance.protocol = ctx.accounts.protocol.key();
+    governance.timelock_secs = timelock_secs;
+    governance.bump = *ctx.bumps.get("governance").ok_or(ErrorCode::InvalidPda)?;
+
+    ctx.accounts.protocol.governance = governance.key();
+
+    emit!(GovernanceInitialized {
+        protocol: governance.protocol,
+        governance: governance.key(),
+        authority: ctx.accounts.authority.key(),
+        timelock_secs,
+    });
+
+    Ok(())
+}
+
+/// Risk admin: queue a parameter change, executable once the timelock has elapsed.
+pub fn handle_queue_change(
+    ctx: Context<QueueChange>,
+    proposal_id: u64,
+    action: GovernanceAction,
+) -> Result<()> {
+    require!(action.is_within_bounds(), ErrorCode::InvalidParameter);
+
+    let governance = &ctx.accounts.governance;
+    let eta = Clock::get()?
+        .unix_timestamp
+        .checked_add(governance.timelock_secs as i64)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.governance = governance.key();
+    proposal.proposer = ctx.accounts.risk_admin.key();
+    proposal.proposal_id = proposal_id;
+    proposal.action = action;
+    proposal.eta = eta;
+    proposal.bump = *ctx.bumps.get("proposal").ok_or(ErrorCode::InvalidPda)?;
+
+    emit!(ChangeQueued {
+        governance: proposal.governance,
+        proposal_id,
+        action,
+        eta,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: apply a queued change once its ETA has passed (within the grace period).
+/// Accounts the change touches are passed in the optional slots of `ExecuteChange`.
+pub fn handle_execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+    let now_ts = Clock::get()?.unix_timestamp;
+    let proposal = &ctx.accounts.proposal;
+    require!(now_ts >= proposal.eta, ErrorCode::TimelockNotElapsed);
+    require!(
+        now_ts <= proposal.eta.saturating_add(PROPOSAL_GRACE_PERIOD_SECS),
+        ErrorCode::ProposalExpired
+    );
+    let action = proposal.action;
+    let proposal_id = proposal.proposal_id;
+    require!(action.is_within_bounds(), ErrorCode::InvalidParameter);
+
+    let protocol_key = ctx.accounts.protocol.key();
+    match action {
+        GovernanceAction::SetGlobalDebtCeiling { debt_ceiling } => {
+            let protocol = &mut ctx.accounts.protocol;
+            let old = protocol.global_debt_ceiling;
+            protocol.global_debt_ceiling = debt_ceiling;
+            emit_parameter_updated(protocol_key, "global_ceiling", old, debt_ceiling);
+        }
+        GovernanceAction::UpdateCollateralParams { collateral_config, params } => {
+            let cfg = ctx
+                .accounts
+                .collateral_config
+                .as_mut()
+                .filter(|cfg| cfg.key() == collateral_config)
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            apply_collateral_params(protocol_key, cfg, &params, now_ts)?;
+        }
+        GovernanceAction::SetStabilityFee { collateral_config, stability_fee_bps } => {
+            let cfg = ctx
+                .accounts
+                .collateral_config
+                .as_mut()
+                .filter(|cfg| cfg.key() == collateral_config)
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            // Fees up to now accrue at the old rate
+            accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+            let old = cfg.stability_fee_bps;
+            cfg.stability_fee_bps = stability_fee_bps;
+            emit_parameter_updated(protocol_key, "stability_fee", old as u64, stability_fee_bps as u64);
+        }
+        GovernanceAction::SetSavingsRate { savings_rate_bps } => {
+            let vault = ctx
+                .accounts
+                .savings_vault
+                .as_mut()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let share_mint = ctx
+                .accounts
+                .share_mint
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let savings_pusd = ctx
+                .accounts
+                .savings_pusd
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let treasury = ctx
+                .accounts
+                .treasury
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let treasury_pusd = ctx
+                .accounts
+                .treasury_pusd
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let pusd_mint = ctx
+                .accounts
+                .pusd_mint
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            let token_program = ctx
+                .accounts
+                .token_program
+                .as_ref()
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            require!(
+                share_mint.key() == vault.share_mint
+                    && savings_pusd.key() == vault.pusd_account
+                    && treasury_pusd.key() == treasury.pusd_account,
+                ErrorCode::GovernanceTargetMismatch
+            );
+
+            // Interest up to now accrues at the old rate
+            accrue_savings(
+                vault,
+                share_mint.supply,
+                treasury,
+                treasury_pusd,
+                pusd_mint,
+                savings_pusd.to_account_info(),
+                token_program.to_account_info(),
+                now_ts,
+            )?;
+            let old = vault.savings_rate_bps;
+            vault.savings_rate_bps = savings_rate_bps;
+            emit_parameter_updated(
+                protocol_key,
+                "savings_rate_bps",
+                old as u64,
+                savings_rate_bps as u64,
+            );
+        }
+        GovernanceAction::SetTimelock { timelock_secs } => {
+            let governance = &mut ctx.accounts.governance;
+            let old = governance.timelock_secs;
+            governance.timelock_secs = timelock_secs;
+            emit_parameter_updated(protocol_key, "timelock_secs", old, timelock_secs);
+        }
+        GovernanceAction::UpdatePsmParams { psm_config, params } => {
+            let psm = ctx
+                .accounts
+                .psm_config
+                .as_mut()
+                .filter(|psm| psm.key() == psm_config)
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            apply_psm_params(protocol_key, psm, &params);
+        }
+        GovernanceAction::SetFinalPrice { collateral_config, final_price_6dp } => {
+            let cfg = ctx
+                .accounts
+                .collateral_config
+                .as_mut()
+                .filter(|cfg| cfg.key() == collateral_config)
+                .ok_or(ErrorCode::GovernanceTargetMismatch)?;
+            freeze_final_price(&mut ctx.accounts.protocol, cfg, final_price_6dp, now_ts)?;
+        }
+    }
+
+    emit!(ChangeExecuted {
+        governance: ctx.accounts.governance.key(),
+        proposal_id,
+        action,
+    });
+
+    Ok(())
+}
+
+/// Risk admin: drop a queued change before it executes.
+pub fn handle_cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+
+    emit!(ChangeCancelled {
+        governance: proposal.governance,
+        proposal_id: proposal.proposal_id,
+        action: proposal.action,
+    });
+
+    Ok(())
+}
+
+/// Root admin: trigger global settlement. Minting, repayment, withdrawals, liquidations,
+/// redemptions and PSM swap-ins stop for good; PSM swap-outs stay open until `thaw`.
+pub fn handle_cage(ctx: Context<Cage>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    require!(
+        protocol.settlement_phase == SettlementPhase::Live,
+        ErrorCode::ProtocolCaged
+    );
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    let settlement = &mut ctx.accounts.settlement;
+    settlement.protocol = protocol.key();
+    settlement.caged_at = now_ts;
+    settlement.thawed_at = 0;
+    settlement.pusd_supply = 0;
+    settlement.bump = *ctx.bumps.get("settlement").ok_or(ErrorCode::InvalidPda)?;
+
+    protocol.settlement_phase = SettlementPhase::Caged;
+
+    emit!(Caged {
+        protocol: protocol.key(),
+        settlement: settlement.key(),
+        caged_at: now_ts,
+    });
+
+    Ok(())
+}
+
+/// Root admin: freeze a collateral's settlement price at its oracle price after `cage`.
+/// Oracle accounts are passed as remaining accounts, in the order registered on the collateral
+/// config. Collateral whose feeds can no longer report is priced through governance
+/// (`GovernanceAction::SetFinalPrice`), under the timelock.
+pub fn handle_cage_collateral(ctx: Context<CageCollateral>) -> Result<()> {
+    let price_6dp: u64 = collateral_price(ctx.remaining_accounts, &ctx.accounts.collateral_config)?
+        .price_6dp
+        .try_into()
+        .map_err(|_| ErrorCode::PriceOutOfBounds)?;
+    let now_ts = Clock::get()?.unix_timestamp;
+    freeze_final_price(
+        &mut ctx.accounts.protocol,
+        &mut ctx.accounts.collateral_config,
+        price_6dp,
+        now_ts,
+    )
+}
+
+/// Permissionless: settle a position's debt at the frozen price. The collateral covering
+/// the debt stays in the vault for PUSD holders; the owner may free the rest.
+pub fn handle_settle_position(ctx: Context<SettlePosition>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    let cfg = &mut ctx.accounts.collateral_config;
+    require!(cfg.final_price_6dp > 0, ErrorCode::SettlementNotReady);
+
+    let pos = &mut ctx.accounts.position;
+    let now_ts = Clock::get()?.unix_timestamp;
+    sync_position_debt(pos, cfg, now_ts)?;
+    require!(pos.normalized_debt > 0, ErrorCode::ZeroAmount);
+
+    let debt = pos.debt_pusd;
+    let (owed, shortfall) = settle_position_at_final_price(
+        &mut ctx.accounts.protocol,
+        cfg,
+        pos,
+        ctx.accounts.collateral_mint.decimals,
+    )?;
+
+    emit!(PositionSettled {
+        owner: pos.owner,
+        collateral_mint: cfg.collateral_mint,
+        debt_pusd_6dp: debt,
+        collateral_amount: owed,
+        shortfall_pusd_6dp: shortfall,
+    });
+
+    Ok(())
+}
+
+/// Owner: withdraw all collateral left in a debt-free (or settled) position after `cage`.
+pub fn handle_free_collateral(ctx: Context<FreeCollateral>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    let pos = &mut ctx.accounts.position;
+    require!(pos.owner == ctx.accounts.owner.key(), ErrorCode::Unauthorized);
+    require!(pos.normalized_debt == 0, ErrorCode::SettlementNotReady);
+    let amount = pos.collateral_amount;
+    require!(amount > 0, ErrorCode::ZeroAmount);
+
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.user_collateral_ata.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    pos.collateral_amount = 0;
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(CollateralWithdrawn {
+        owner: pos.owner,
+        collateral_mint: cfg.collateral_mint,
+        withdrawn_amount: amount,
+        remaining_collateral_amount: 0,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: once SETTLEMENT_THAW_DELAY_SECS have passed since `cage`, fix the
+/// outstanding PUSD supply that the collateral set aside is shared across. The treasury's
+/// PUSD is burned first: it is protocol surplus, and no one could pack it. PUSD in the savings
+/// vault and stability pools belongs to depositors, who withdraw and pack it themselves.
+pub fn handle_thaw(ctx: Context<Thaw>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase == SettlementPhase::Caged,
+        ErrorCode::ProtocolNotCaged
+    );
+
+    let now_ts = Clock::get()?.unix_timestamp;
+    let thaw_at = ctx
+        .accounts
+        .settlement
+        .caged_at
+        .checked_add(SETTLEMENT_THAW_DELAY_SECS)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(now_ts >= thaw_at, ErrorCode::SettlementNotReady);
+
+    let surplus = ctx.accounts.treasury_pusd.amount;
+    let pusd_supply = settlement_pusd_supply(ctx.accounts.pusd_mint.supply, surplus)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(pusd_supply > 0, ErrorCode::InvalidAmount);
+
+    if surplus > 0 {
+        let treasury = &ctx.accounts.treasury;
+        let seeds = [
+            Treasury::SEED_PREFIX,
+            treasury.protocol.as_ref(),
+            &[treasury.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token_interface::Burn {
+            mint: ctx.accounts.pusd_mint.to_account_info(),
+            from: ctx.accounts.treasury_pusd.to_account_info(),
+            authority: ctx.accounts.treasury.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token_interface::burn(cpi_ctx, surplus)?;
+    }
+
+    let settlement = &mut ctx.accounts.settlement;
+    settlement.pusd_supply = pusd_supply;
+    settlement.thawed_at = now_ts;
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.settlement_phase = SettlementPhase::Thawed;
+
+    emit!(Thawed {
+        protocol: protocol.key(),
+        pusd_supply,
+        thawed_at: now_ts,
+    });
+
+    Ok(())
+}
+
+/// Holder: burn PUSD into a settlement claim, to be cashed against each collateral.
+pub fn handle_pack_pusd(ctx: Context<PackPusd>, pusd_amount: u64) -> Result<()> {
+    require!(pusd_amount > 0, ErrorCode::ZeroAmount);
+    require!(
+        ctx.accounts.protocol.settlement_phase == SettlementPhase::Thawed,
+        ErrorCode::SettlementNotReady
+    );
+
+    let cpi_accounts = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.owner_pusd_ata.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::burn(cpi_ctx, pusd_amount)?;
+
+    let claim = &mut ctx.accounts.claim;
+    if claim.owner == Pubkey::default() {
+        claim.settlement = ctx.accounts.settlement.key();
+        claim.owner = ctx.accounts.owner.key();
+        claim.pusd_packed = 0;
+        claim.bump = *ctx.bumps.get("claim").ok_or(ErrorCode::InvalidPda)?;
+    }
+    claim.pusd_packed = claim
+        .pusd_packed
+        .checked_add(pusd_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(PusdPacked {
+        owner: claim.owner,
+        pusd_amount,
+        total_packed: claim.pusd_packed,
+    });
+
+    Ok(())
+}
+
+/// Holder: cash packed PUSD for its pro-rata share of one collateral. Available once every
+/// indebted position of that collateral has been settled; repeat per collateral.
+pub fn handle_cash_collateral(ctx: Context<CashCollateral>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase == SettlementPhase::Thawed,
+        ErrorCode::SettlementNotReady
+    );
+    let cfg = &ctx.accounts.collateral_config;
+    require!(cfg.settlement_cashable(), ErrorCode::SettlementNotReady);
+
+    let claim = &ctx.accounts.claim;
+    let cash = &mut ctx.accounts.cash;
+    if cash.claim == Pubkey::default() {
+        cash.claim = claim.key();
+        cash.collateral_config = cfg.key();
+        cash.cashed_pusd = 0;
+        cash.bump = *ctx.bumps.get("cash").ok_or(ErrorCode::InvalidPda)?;
+    }
+    let pusd_amount = claim
+        .pusd_packed
+        .checked_sub(cash.cashed_pusd)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(pusd_amount > 0, ErrorCode::ZeroAmount);
+
+    let collateral_amount = settlement_cash_amount(
+        pusd_amount,
+        cfg.settlement_collateral,
+        ctx.accounts.settlement.pusd_supply,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    cash.cashed_pusd = claim.pusd_packed;
+
+    if collateral_amount > 0 {
+        transfer_from_vault(
+            &ctx.accounts.collateral_config,
+            &ctx.accounts.collateral_mint,
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.owner_collateral_ata.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            collateral_amount,
+        )?;
+    }
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(collateral_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(SettlementCashed {
+        owner: ctx.accounts.owner.key(),
+        collateral_mint: cfg.collateral_mint,
+        pusd_amount,
+        collateral_amount,
+    });
+
+    Ok(())
+}
+
+/// Open a cross-margin account. Its debt is booked under `debt_collateral_config`; collateral
+/// of any registered type is added with `handle_deposit_cross_margin`.
+pub fn handle_open_cross_margin(ctx: Context<OpenCrossMargin>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase == SettlementPhase::Live,
+        ErrorCode::ProtocolCaged
+    );
+    let cfg = &ctx.accounts.debt_collateral_config;
+    require!(cfg.active, ErrorCode::CollateralInactive);
+
+    let account = &mut ctx.accounts.cross_margin;
+    account.owner = ctx.accounts.owner.key();
+    account.protocol = ctx.accounts.protocol.key();
+    account.debt_collateral_config = cfg.key();
+    account.balances = [CrossMarginBalance::default(); MAX_CROSS_MARGIN_COLLATERALS];
+    account.balance_count = 0;
+    account.debt_pusd = 0;
+    account.normalized_debt = 0;
+    account.last_accrual_ts = Clock::get()?.unix_timestamp;
+    account.bump = *ctx.bumps.get("cross_margin").ok_or(ErrorCode::InvalidPda)?;
+    account.settled = false;
+
+    emit!(CrossMarginOpened {
+        owner: account.owner,
+        account: account.key(),
+        debt_collateral_mint: cfg.collateral_mint,
+    });
+
+    Ok(())
+}
+
+/// Deposit collateral into a cross-margin account, adding a balance for a new collateral type.
+pub fn handle_deposit_cross_margin(
+    ctx: Context<DepositCrossMargin>,
+    deposit_amount: u64,
+) -> Result<()> {
+    require!(deposit_amount > 0, ErrorCode::ZeroAmount);
+    let cfg = &ctx.accounts.collateral_config;
+    require!(cfg.active, ErrorCode::CollateralInactive);
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_DEPOSIT)?;
+
+    let account = &ctx.accounts.cross_margin;
+    let index = match account.balance_index(&cfg.key()) {
+        Some(index) => index,
+        None => {
+            require!(
+                (account.balance_count as usize) < MAX_CROSS_MARGIN_COLLATERALS,
+                ErrorCode::CrossMarginFull
+            );
+            account.balance_count as usize
+        }
+    };
+
+    // Credit only what the vault actually received (transfer-fee mints deliver less)
+    let vault_before = ctx.accounts.vault.amount;
+    let cpi_accounts = token_interface::TransferChecked {
+        from: ctx.accounts.user_collateral_ata.to_account_info(),
+        mint: ctx.accounts.collateral_mint.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, deposit_amount, ctx.accounts.collateral_mint.decimals)?;
+    ctx.accounts.vault.reload()?;
+    let received = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(received > 0, ErrorCode::ZeroAmount);
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    let account = &mut ctx.accounts.cross_margin;
+    if index == account.balance_count as usize {
+        account.balances[index] = CrossMarginBalance {
+            collateral_config: cfg.key(),
+            amount: 0,
+        };
+        account.balance_count += 1;
+        cfg.cross_margin_balances = cfg
+            .cross_margin_balances
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    let balance = &mut account.balances[index];
+    balance.amount = balance
+        .amount
+        .checked_add(received)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_add(received)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(CrossMarginDeposited {
+        owner: account.owner,
+        collateral_mint: cfg.collateral_mint,
+        deposited_amount: received,
+        balance_amount: account.balances[index].amount,
+    });
+
+    Ok(())
+}
+
+/// Mint PUSD against every balance of a cross-margin account.
+/// Remaining accounts hold, for each balance in order: its CollateralConfig, its collateral
+/// mint, then its price accounts (see `value_cross_margin`).
+pub fn handle_mint_cross_margin<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MintCrossMargin<'info>>,
+    mint_pusd_6dp: u64,
+) -> Result<()> {
+    require!(mint_pusd_6dp > 0, ErrorCode::ZeroAmount);
+    let cfg = &mut ctx.accounts.debt_collateral_config;
+    require!(cfg.active, ErrorCode::CollateralInactive);
+    require_not_paused(&ctx.accounts.protocol, cfg.paused_actions, PAUSE_MINT)?;
+
+    // Realize stability fees before checking limits
+    let account = &mut ctx.accounts.cross_margin;
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_cross_margin_debt(account, cfg, now_ts)?;
+    let protocol = &ctx.accounts.protocol;
+
+    // Every balance counts at the low end of its confidence band, weighted by its initial LTV
+    let valuation = value_cross_margin(account, ctx.remaining_accounts, false)?;
+    let new_debt = account
+        .debt_pusd
+        .checked_add(mint_pusd_6dp)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        cross_margin_within_initial_ltv(valuation.legs(), new_debt as u128),
+        ErrorCode::LtvExceeded
+    );
+
+    // Global and debt-collateral ceilings
+    let new_total_debt = debt_after_mint(
+        protocol.total_debt_pusd,
+        mint_pusd_6dp,
+        protocol.global_debt_ceiling,
+    )
+    .ok_or(ErrorCode::GlobalDebtCeilingReached)?;
+
+    let new_collateral_debt = debt_after_mint(cfg.total_debt_pusd, mint_pusd_6dp, cfg.debt_ceiling)
+        .ok_or(ErrorCode::CollateralDebtCeilingReached)?;
+
+    let seeds = [
+        Protocol::SEED_PREFIX,
+        protocol.seed_key.as_ref(),
+        &[protocol.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::MintTo {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        to: ctx.accounts.user_pusd_ata.to_account_info(),
+        authority: ctx.accounts.protocol.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::mint_to(cpi_ctx, mint_pusd_6dp)?;
+
+    ctx.accounts.protocol.total_debt_pusd = new_total_debt;
+    cfg.total_debt_pusd = new_collateral_debt;
+    increase_cross_margin_debt(account, cfg, mint_pusd_6dp)?;
+
+    emit!(CrossMarginMinted {
+        owner: account.owner,
+        minted_pusd_6dp: mint_pusd_6dp,
+        new_debt_pusd_6dp: account.debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Burn/repay PUSD owed by a cross-margin account.
+pub fn handle_burn_cross_margin(ctx: Context<BurnCrossMargin>, burn_pusd_6dp: u64) -> Result<()> {
+    require!(burn_pusd_6dp > 0, ErrorCode::ZeroAmount);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.debt_collateral_config.paused_actions,
+        PAUSE_BURN,
+    )?;
+
+    let cpi_accounts = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.user_pusd_ata.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::burn(cpi_ctx, burn_pusd_6dp)?;
+
+    let account = &mut ctx.accounts.cross_margin;
+    let cfg = &mut ctx.accounts.debt_collateral_config;
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, cfg, now_ts)?;
+    sync_cross_margin_debt(account, cfg, now_ts)?;
+    decrease_cross_margin_debt(account, cfg, burn_pusd_6dp)?;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, cfg, burn_pusd_6dp);
+
+    emit!(CrossMarginBurned {
+        owner: account.owner,
+        burned_pusd_6dp: burn_pusd_6dp,
+        new_debt_pusd_6dp: account.debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Withdraw collateral from one balance of a cross-margin account.
+/// With debt outstanding, the remaining balances must still satisfy their initial LTVs; the
+/// remaining accounts then cover every balance held before the withdrawal, as for minting.
+pub fn handle_withdraw_cross_margin<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawCrossMargin<'info>>,
+    withdraw_amount: u64,
+) -> Result<()> {
+    require!(withdraw_amount > 0, ErrorCode::ZeroAmount);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.collateral_config.paused_actions,
+        PAUSE_WITHDRAW,
+    )?;
+
+    // Realize stability fees so the LTV check sees the full debt
+    let account = &mut ctx.accounts.cross_margin;
+    let debt_cfg = match ctx.accounts.debt_collateral_config.as_mut() {
+        Some(debt_cfg) => debt_cfg,
+        None => &mut ctx.accounts.collateral_config,
+    };
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, debt_cfg, now_ts)?;
+    sync_cross_margin_debt(account, debt_cfg, now_ts)?;
+
+    let index = account
+        .balance_index(&ctx.accounts.collateral_config.key())
+        .ok_or(ErrorCode::UnsupportedCollateral)?;
+    let balance = &mut account.balances[index];
+    require!(withdraw_amount <= balance.amount, ErrorCode::InsufficientCollateral);
+    balance.amount -= withdraw_amount;
+    let remaining_amount = balance.amount;
+
+    // Debt-free accounts can always withdraw; otherwise what is left must back the debt
+    if account.debt_pusd > 0 {
+        let valuation = value_cross_margin(account, ctx.remaining_accounts, false)?;
+        require!(
+            cross_margin_within_initial_ltv(valuation.legs(), account.debt_pusd as u128),
+            ErrorCode::LtvExceeded
+        );
+    }
+    if remaining_amount == 0 {
+        account.remove_balance(index);
+    }
+
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.user_collateral_ata.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        withdraw_amount,
+    )?;
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(withdraw_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    if remaining_amount == 0 {
+        release_cross_margin_balance(cfg)?;
+    }
+
+    emit!(CrossMarginWithdrawn {
+        owner: ctx.accounts.cross_margin.owner,
+        collateral_mint: cfg.collateral_mint,
+        withdrawn_amount: withdraw_amount,
+        balance_amount: remaining_amount,
+    });
+
+    Ok(())
+}
+
+/// Liquidate an unhealthy cross-margin account by repaying PUSD in exchange for discounted
+/// collateral from one of its balances. The account is unhealthy once its debt exceeds what
+/// its balances support at their maintenance LTVs; remaining accounts are laid out as for
+/// minting. The close factor, dust threshold and penalty are those of the debt collateral;
+/// the bonus is that of the seized collateral.
+pub fn handle_liquidate_cross_margin<'info>(
+    ctx: Context<'_, '_, 'info, 'info, LiquidateCrossMargin<'info>>,
+    repay_pusd_6dp: u64,
+) -> Result<()> {
+    require!(repay_pusd_6dp > 0, ErrorCode::ZeroAmount);
+    require_not_paused(
+        &ctx.accounts.protocol,
+        ctx.accounts.collateral_config.paused_actions,
+        PAUSE_LIQUIDATE,
+    )?;
+    let seized_cfg_key = ctx.accounts.collateral_config.key();
+    let liq_bonus_bps = ctx.accounts.collateral_config.liq_bonus_bps;
+
+    // Realize stability fees so eligibility is judged on the full debt
+    let account = &mut ctx.accounts.cross_margin;
+    let debt_cfg = match ctx.accounts.debt_collateral_config.as_mut() {
+        Some(debt_cfg) => debt_cfg,
+        None => &mut ctx.accounts.collateral_config,
+    };
+    let now_ts = Clock::get()?.unix_timestamp;
+    accrue_stability_fees(&mut ctx.accounts.protocol, debt_cfg, now_ts)?;
+    sync_cross_margin_debt(account, debt_cfg, now_ts)?;
+
+    let valuation = value_cross_margin(account, ctx.remaining_accounts, true)?;
+    require!(
+        !cross_margin_above_maintenance(valuation.legs(), account.debt_pusd as u128),
+        ErrorCode::NotLiquidatable
+    );
+    let index = account
+        .balance_index(&seized_cfg_key)
+        .ok_or(ErrorCode::UnsupportedCollateral)?;
+
+    // Target health is judged on the account's total collateral value
+    let total_value_6dp = valuation
+        .legs()
+        .iter()
+        .fold(0u128, |sum, (value, _)| sum.saturating_add(*value));
+    let (max_repay, full_liquidation) =
+        max_liquidation_repay(debt_cfg, total_value_6dp, account.debt_pusd)?;
+    require!(
+        full_liquidation || repay_pusd_6dp <= max_repay,
+        ErrorCode::LiquidationTooLarge
+    );
+
+    let penalty = liquidation_penalty_pusd(debt_cfg, repay_pusd_6dp.min(account.debt_pusd))?;
+    if penalty > 0 {
+        increase_cross_margin_debt(account, debt_cfg, penalty)?;
+        book_fee(&mut ctx.accounts.protocol, debt_cfg, penalty, FEE_SOURCE_LIQ_PENALTY)?;
+    }
+
+    // Cap repay to current debt (including the penalty); only reachable on full liquidations
+    let repay = repay_pusd_6dp.min(account.debt_pusd);
+
+    let cpi_burn = token_interface::Burn {
+        mint: ctx.accounts.pusd_mint.to_account_info(),
+        from: ctx.accounts.liquidator_pusd_ata.to_account_info(),
+        authority: ctx.accounts.liquidator.to_account_info(),
+    };
+    let cpi_ctx_burn =
+        CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_burn);
+    token_interface::burn(cpi_ctx_burn, repay)?;
+
+    let seize_amount = seize_for_repay(
+        repay,
+        ctx.accounts.collateral_mint.decimals,
+        valuation.prices_6dp[index],
+        liq_bonus_bps,
+    )?
+    .min(account.balances[index].amount);
+    require!(seize_amount > 0, ErrorCode::InvalidAmount);
+
+    account.balances[index].amount -= seize_amount;
+    let emptied = account.balances[index].amount == 0;
+    if emptied {
+        account.remove_balance(index);
+    }
+
+    decrease_cross_margin_debt(account, debt_cfg, repay)?;
+    reduce_aggregate_debt(&mut ctx.accounts.protocol, debt_cfg, repay);
+    recognize_cross_margin_bad_debt(&mut ctx.accounts.protocol, debt_cfg, account)?;
+
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.liquidator_collateral_ata.to_account_info(),
+        ctx.accounts.collateral_token_program.to_account_info(),
+        seize_amount,
+    )?;
+
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(seize_amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    if emptied {
+        release_cross_margin_balance(cfg)?;
+    }
+
+    emit!(CrossMarginLiquidated {
+        liquidator: ctx.accounts.liquidator.key(),
+        owner: ctx.accounts.cross_margin.owner,
+        collateral_mint: cfg.collateral_mint,
+        repaid_pusd_6dp: repay,
+        seized_collateral_amount: seize_amount,
+    });
+
+    Ok(())
+}
+
+/// Permissionless: settle a cross-margin account's debt at the frozen prices, taking
+/// collateral from its balances in order until the debt is covered. Debt-free accounts are
+/// settled too, so their balances stop holding up `cash_collateral`. Remaining accounts hold,
+/// for each balance in order: its CollateralConfig (writable) and its collateral mint.
+pub fn handle_settle_cross_margin<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleCrossMargin<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    let debt_cfg = &mut ctx.accounts.debt_collateral_config;
+    require!(debt_cfg.final_price_6dp > 0, ErrorCode::SettlementNotReady);
+
+    let account = &mut ctx.accounts.cross_margin;
+    require!(!account.settled, ErrorCode::AlreadySettled);
+    let now_ts = Clock::get()?.unix_timestamp;
+    sync_cross_margin_debt(account, debt_cfg, now_ts)?;
+
+    let count = account.balance_count as usize;
+    require!(
+        ctx.remaining_accounts.len() >= count * 2,
+        ErrorCode::UnsupportedCollateral
+    );
+    let debt = account.debt_pusd;
+    let mut uncovered = debt;
+    for index in 0..count {
+        let balance = account.balances[index];
+        let cfg_info = &ctx.remaining_accounts[index * 2];
+        require_keys_eq!(
+            cfg_info.key(),
+            balance.collateral_config,
+            ErrorCode::UnsupportedCollateral
+        );
+
+        // The debt collateral is written back through its named account, every other one here
+        let owed = if balance.collateral_config == debt_cfg.key() {
+            settle_cross_margin_leg(
+                debt_cfg,
+                &ctx.remaining_accounts[index * 2 + 1],
+                balance.amount,
+                &mut uncovered,
+            )?
+        } else {
+            let mut cfg = Account::<CollateralConfig>::try_from(cfg_info)?;
+            let owed = settle_cross_margin_leg(
+                &mut cfg,
+                &ctx.remaining_accounts[index * 2 + 1],
+                balance.amount,
+                &mut uncovered,
+            )?;
+            cfg.exit(ctx.program_id)?;
+            owed
+        };
+        account.balances[index].amount -= owed;
+    }
+    for index in (0..count).rev() {
+        if account.balances[index].amount == 0 {
+            account.remove_balance(index);
+        }
+    }
+    account.settled = true;
+
+    if debt > 0 {
+        decrease_cross_margin_debt(account, debt_cfg, debt)?;
+        reduce_aggregate_debt(&mut ctx.accounts.protocol, debt_cfg, debt);
+        debt_cfg.settlement_shortfall_pusd = debt_cfg
+            .settlement_shortfall_pusd
+            .checked_add(uncovered)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    emit!(CrossMarginSettled {
+        owner: account.owner,
+        debt_pusd_6dp: debt,
+        shortfall_pusd_6dp: uncovered,
+    });
+
+    Ok(())
+}
+
+/// Owner: withdraw one whole balance of a settled cross-margin account after `cage`.
+pub fn handle_free_cross_margin_collateral(ctx: Context<FreeCrossMarginCollateral>) -> Result<()> {
+    require!(
+        ctx.accounts.protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    let account = &mut ctx.accounts.cross_margin;
+    require!(account.settled, ErrorCode::SettlementNotReady);
+    let index = account
+        .balance_index(&ctx.accounts.collateral_config.key())
+        .ok_or(ErrorCode::UnsupportedCollateral)?;
+    let amount = account.balances[index].amount;
+    require!(amount > 0, ErrorCode::ZeroAmount);
+
+    transfer_from_vault(
+        &ctx.accounts.collateral_config,
+        &ctx.accounts.collateral_mint,
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.user_collateral_ata.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    account.remove_balance(index);
+    let cfg = &mut ctx.accounts.collateral_config;
+    cfg.total_collateral = cfg
+        .total_collateral
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    emit!(CrossMarginWithdrawn {
+        owner: account.owner,
+        collateral_mint: cfg.collateral_mint,
+        withdrawn_amount: amount,
+        balance_amount: 0,
+    });
+
+    Ok(())
+}
+
+/// ===============================
+/// Helpers
+/// ===============================
+
+/// Fail with the action's error if it is paused protocol-wide or for this collateral.
+/// Every action is off for good once the protocol is caged.
+pub fn require_not_paused(protocol: &Protocol, collateral_paused: u8, action: u8) -> Result<()> {
+    require!(
+        protocol.settlement_phase == SettlementPhase::Live,
+        ErrorCode::ProtocolCaged
+    );
+    require_action_not_paused(protocol, collateral_paused, action)
+}
+
+/// Pause check alone, for the few actions that stay open after `cage` (PSM exits).
+pub fn require_action_not_paused(protocol: &Protocol, collateral_paused: u8, action: u8) -> Result<()> {
+    if !is_action_paused(protocol.paused_actions, collateral_paused, action) {
+        return Ok(());
+    }
+    let code = match action {
+        PAUSE_MINT => ErrorCode::MintPaused,
+        PAUSE_DEPOSIT => ErrorCode::DepositPaused,
+        PAUSE_BURN => ErrorCode::BurnPaused,
+        PAUSE_WITHDRAW => ErrorCode::WithdrawPaused,
+        PAUSE_LIQUIDATE => ErrorCode::LiquidationPaused,
+        _ => ErrorCode::PsmPaused,
+    };
+    Err(code.into())
+}
+
+/// Grow the collateral's rate index up to `now_ts` and book the fee into aggregate debt.
+/// Returns the fee accrued (PUSD, 6dp).
+pub fn accrue_stability_fees(
+    protocol: &mut Account<Protocol>,
+    cfg: &mut CollateralConfig,
+    now_ts: i64,
+) -> Result<u64> {
+    let elapsed = now_ts.saturating_sub(cfg.last_accrual_ts);
+    // Debt stops growing once the settlement price is frozen
+    if elapsed <= 0 || cfg.final_price_6dp > 0 {
+        return Ok(0);
+    }
+
+    let old_index = cfg.rate_index;
+    let new_index = accrue_rate_index(old_index, cfg.stability_fee_bps, elapsed)
+        .ok_or(ErrorCode::MathOverflow)?;
+    cfg.rate_index = new_index;
+    cfg.last_accrual_ts = now_ts;
+
+    // fee = normalized_debt * (new_index - old_index) / ONE
+    let fee: u64 = cfg
+        .total_normalized_debt
+        .checked_mul(new_index - old_index)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(RATE_INDEX_ONE)
+        .ok_or(ErrorCode::DivisionByZero)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    book_fee(protocol, cfg, fee, FEE_SOURCE_STABILITY_FEE)?;
+
+    emit!(StabilityFeeAccrued {
+        collateral_mint: cfg.collateral_mint,
+        rate_index: new_index,
+        accrued_fee_pusd_6dp: fee,
+        total_debt_pusd_6dp: cfg.total_debt_pusd,
+    });
+
+    Ok(fee)
+}
+
+/// Whether a position with `debt_pusd_6dp` may shrink its collateral from `collateral_amount`
+/// to `remaining_collateral` at `price_6dp`: it must be above maintenance before the
+/// withdrawal and still within its initial LTV after it.
+pub fn check_withdrawal_ltv(
+    collateral_amount: u64,
+    remaining_collateral: u64,
+    debt_pusd_6dp: u64,
+    collateral_decimals: u8,
+    price_6dp: u128,
+    initial_ltv_bps: u16,
+    maintenance_ltv_bps: u16,
+) -> Result<()> {
+    let current_value_6dp = token_amount_to_usd_6dp(collateral_amount, collateral_decimals, price_6dp)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        is_above_maintenance(current_value_6dp, debt_pusd_6dp as u128, maintenance_ltv_bps),
+        ErrorCode::HealthBelowMaintenance
+    );
+
+    let remaining_value_6dp =
+        token_amount_to_usd_6dp(remaining_collateral, collateral_decimals, price_6dp)
+            .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        check_mint_within_initial_ltv(remaining_value_6dp, debt_pusd_6dp as u128, 0, initial_ltv_bps),
+        ErrorCode::LtvExceeded
+    );
+    Ok(())
+}
+
+/// Largest principal repayment allowed in one liquidation, and whether the position may be
+/// fully liquidated instead (dust-sized, or a capped repayment would leave dust behind).
+pub fn max_liquidation_repay(
+    cfg: &CollateralConfig,
+    collateral_value_6dp: u128,
+    debt_pusd: u64,
+) -> Result<(u64, bool)> {
+    if debt_pusd <= cfg.dust_threshold_pusd {
+        return Ok((debt_pusd, true));
+    }
+
+    let mut max_repay = (debt_pusd as u128)
+        .checked_mul(cfg.close_factor_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR;
+    if cfg.target_health_bps > 0 {
+        if let Some(to_target) = repay_to_target_health(
+            collateral_value_6dp,
+            debt_pusd as u128,
+            cfg.target_health_bps,
+            cfg.liq_bonus_bps,
+            cfg.liq_penalty_bps,
+        ) {
+            max_repay = max_repay.min(to_target);
+        }
+    }
+    let max_repay = max_repay.min(debt_pusd as u128) as u64;
+
+    if debt_pusd - max_repay <= cfg.dust_threshold_pusd {
+        return Ok((debt_pusd, true));
+    }
+    Ok((max_repay, false))
+}
+
+/// Liquidation penalty (PUSD) charged on `liquidated_debt`.
+pub fn liquidation_penalty_pusd(cfg: &CollateralConfig, liquidated_debt: u64) -> Result<u64> {
+    let penalty = (liquidated_debt as u128)
+        .checked_mul(cfg.liq_penalty_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / BPS_DENOMINATOR;
+    let penalty: u64 = penalty.try_into().map_err(|_| ErrorCode::MathOverflow)?;
+    Ok(penalty)
+}
+
+/// Collateral (smallest units) paid out for `repay` PUSD at `price_6dp`, including the bonus.
+pub fn seize_for_repay(
+    repay: u64,
+    collateral_decimals: u8,
+    price_6dp: u128,
+    liq_bonus_bps: u16,
+) -> Result<u64> {
+    require!(price_6dp > 0, ErrorCode::PriceOutOfBounds);
+
+    // price_6dp = USD per 1 whole token; convert repay USD into token amount (inverse pricing).
+    // tokens = (repay_6dp / price_6dp) * 10^decimals
+    let num = (repay as u128)
+        .checked_mul(crate::state::ten_pow_u128(collateral_decimals as u32))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let base_tokens = num.checked_div(price_6dp).ok_or(ErrorCode::DivisionByZero)?;
+    let seized_with_bonus =
+        apply_liquidation_bonus_bps(base_tokens, liq_bonus_bps).ok_or(ErrorCode::MathOverflow)?;
+
+    let seize_amount: u64 = seized_with_bonus
+        .try_into()
+        .map_err(|_| ErrorCode::InvalidAmount)?;
+    Ok(seize_amount)
+}
+
+/// Collateral exhausted with debt left over: nobody will repay it, so move it to bad debt.
+pub fn recognize_bad_debt(
+    protocol: &mut Account<Protocol>,
+    cfg: &mut CollateralConfig,
+    pos: &mut Position,
+) -> Result<()> {
+    let residual = unbacked_debt(pos.collateral_amount, pos.debt_pusd);
+    if residual == 0 {
+        return Ok(());
+    }
+
+    decrease_position_debt(pos, cfg, residual)?;
+    reduce_aggregate_debt(protocol, cfg, residual);
+    protocol.bad_debt_pusd = protocol
+        .bad_debt_pusd
+        .checked_add(residual)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(BadDebtRecognized {
+        owner: pos.owner,
+        collateral_mint: cfg.collateral_mint,
+        bad_debt_pusd_6dp: residual,
+        total_bad_debt_pusd_6dp: protocol.bad_debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Release collateral from a collateral vault (signed by the CollateralConfig PDA).
+/// Uses `transfer_checked` so Token-2022 collateral mints are supported.
+pub fn transfer_from_vault<'info>(
+    cfg: &Account<'info, CollateralConfig>,
+    collateral_mint: &InterfaceAccount<'info, InterfaceMint>,
+    vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = [
+        CollateralConfig::SEED_PREFIX,
+        cfg.protocol.as_ref(),
+        cfg.collateral_mint.as_ref(),
+        &[cfg.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: vault,
+        mint: collateral_mint.to_account_info(),
+        to,
+        authority: cfg.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, amount, collateral_mint.decimals)
+}
+
+pub fn stability_pool_accumulators(pool: &StabilityPool) -> StabilityPoolAccumulators {
+    StabilityPoolAccumulators {
+        product_p: pool.product_p,
+        sum_s: pool.sum_s,
+        sum_s_previous: pool.sum_s_previous,
+        scale: pool.scale,
+    }
+}
+
+/// Compounded deposit and pending collateral gain for a stability deposit.
+pub fn settle_stability_deposit(pool: &StabilityPool, deposit: &StabilityDeposit) -> Result<(u64, u64)> {
+    let acc = stability_pool_accumulators(pool);
+    let compounded = acc
+        .compounded_deposit(deposit.initial_deposit, deposit.snapshot_p, deposit.snapshot_scale)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let gain = acc
+        .collateral_gain(
+            deposit.initial_deposit,
+            deposit.snapshot_p,
+            deposit.snapshot_s,
+            deposit.snapshot_scale,
+        )
+        .ok_or(ErrorCode::MathOverflow)?
+        // Never pay out more than the pool has earned (rounding)
+        .min(pool.collateral_gains);
+    Ok((compounded, gain))
+}
+
+/// Restart a deposit's accounting from the pool's current accumulators.
+pub fn snapshot_stability_deposit(pool: &StabilityPool, deposit: &mut StabilityDeposit, new_deposit: u64) {
+    deposit.initial_deposit = new_deposit;
+    deposit.snapshot_p = pool.product_p;
+    deposit.snapshot_s = pool.sum_s;
+    deposit.snapshot_scale = pool.scale;
+}
+
+/// Book a collateral gain paid out of the vault to a depositor.
+pub fn pay_stability_pool_gain(
+    pool: &mut StabilityPool,
+    cfg: &mut CollateralConfig,
+    gain: u64,
+) -> Result<()> {
+    pool.collateral_gains = pool.collateral_gains.checked_sub(gain).ok_or(ErrorCode::MathUnderflow)?;
+    cfg.total_collateral = cfg.total_collateral.checked_sub(gain).ok_or(ErrorCode::MathUnderflow)?;
+    Ok(())
+}
+
+/// Book a fee charged as debt: grow aggregate debt and the protocol's accrued surplus.
+/// Fees may push aggregate debt past the ceilings; ceilings only gate new mints.
+pub fn book_fee(
+    protocol: &mut Account<Protocol>,
+    cfg: &mut CollateralConfig,
+    amount: u64,
+    source: &str,
+) -> Result<()> {
+    cfg.total_debt_pusd = cfg.total_debt_pusd.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    protocol.total_debt_pusd = protocol
+        .total_debt_pusd
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    protocol.accrued_surplus_pusd = protocol
+        .accrued_surplus_pusd
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(FeeBooked {
+        protocol: protocol.key(),
+        source: pack_label(source),
+        amount_pusd_6dp: amount,
+        accrued_surplus_pusd_6dp: protocol.accrued_surplus_pusd,
+        realized_fees_pusd_6dp: protocol.realized_fees_pusd,
+    });
+
+    Ok(())
+}
+
+/// Book a fee already paid to the treasury in PUSD (e.g., PSM fees).
+pub fn book_realized_fee(protocol: &mut Account<Protocol>, amount: u64, source: &str) -> Result<()> {
+    protocol.realized_fees_pusd = protocol
+        .realized_fees_pusd
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(FeeBooked {
+        protocol: protocol.key(),
+        source: pack_label(source),
+        amount_pusd_6dp: amount,
+        accrued_surplus_pusd_6dp: protocol.accrued_surplus_pusd,
+        realized_fees_pusd_6dp: protocol.realized_fees_pusd,
+    });
+
+    Ok(())
+}
+
+/// Refresh a position's debt from its normalized debt at the collateral's current index.
+pub fn sync_position_debt(pos: &mut Position, cfg: &CollateralConfig, now_ts: i64) -> Result<()> {
+    pos.debt_pusd =
+        normalized_to_debt(pos.normalized_debt, cfg.rate_index).ok_or(ErrorCode::MathOverflow)?;
+    pos.last_accrual_ts = now_ts;
+    Ok(())
+}
+
+/// Add newly minted debt to a position (normalized amount rounded up).
+pub fn increase_position_debt(pos: &mut Position, cfg: &mut CollateralConfig, amount: u64) -> Result<()> {
+    let normalized =
+        debt_to_normalized(amount, cfg.rate_index, true).ok_or(ErrorCode::MathOverflow)?;
+    pos.normalized_debt = pos
+        .normalized_debt
+        .checked_add(normalized)
+        .ok_or(ErrorCode::MathOverflow)?;
+    cfg.total_normalized_debt = cfg
+        .total_normalized_debt
+        .checked_add(normalized)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pos.debt_pusd =
+        normalized_to_debt(pos.normalized_debt, cfg.rate_index).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove repaid debt from a position (normalized amount rounded down).
+/// Repaying the full synced debt clears the normalized balance so no dust is left behind.
+pub fn decrease_position_debt(pos: &mut Position, cfg: &mut CollateralConfig, amount: u64) -> Result<()> {
+    require!(amount <= pos.debt_pusd, ErrorCode::MathUnderflow);
+    let normalized = if amount == pos.debt_pusd {
+        pos.normalized_debt
+    } else {
+        debt_to_normalized(amount, cfg.rate_index, false)
+            .ok_or(ErrorCode::MathOverflow)?
+            .min(pos.normalized_debt)
+    };
+    pos.normalized_debt -= normalized;
+    cfg.total_normalized_debt = cfg.total_normalized_debt.saturating_sub(normalized);
+    pos.debt_pusd =
+        normalized_to_debt(pos.normalized_debt, cfg.rate_index).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Reduce aggregate debt after a repayment. Positions round their debt up, so the sum of
+/// repayments can exceed the booked aggregate by a few units; saturate instead of failing.
+pub fn reduce_aggregate_debt(protocol: &mut Protocol, cfg: &mut CollateralConfig, amount: u64) {
+    protocol.total_debt_pusd = protocol.total_debt_pusd.saturating_sub(amount);
+    cfg.total_debt_pusd = cfg.total_debt_pusd.saturating_sub(amount);
+}
+
+/// Freeze a collateral's settlement price once the protocol is caged. Fees are booked up to
+/// now; the rate index stays frozen afterwards.
+pub fn freeze_final_price(
+    protocol: &mut Account<Protocol>,
+    cfg: &mut CollateralConfig,
+    final_price_6dp: u64,
+    now_ts: i64,
+) -> Result<()> {
+    require!(
+        protocol.settlement_phase != SettlementPhase::Live,
+        ErrorCode::ProtocolNotCaged
+    );
+    require!(cfg.final_price_6dp == 0, ErrorCode::InvalidParameter);
+    require!(final_price_6dp > 0, ErrorCode::PriceOutOfBounds);
+
+    accrue_stability_fees(protocol, cfg, now_ts)?;
+    cfg.final_price_6dp = final_price_6dp;
+
+    emit!(CollateralCaged {
+        collateral_mint: cfg.collateral_mint,
+        final_price_6dp,
+        total_debt_pusd: cfg.total_debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Settle a position's synced debt at the frozen price: the collateral covering it is set
+/// aside for PUSD holders and the debt leaves the books. Returns (collateral owed, uncovered
+/// debt).
+pub fn settle_position_at_final_price(
+    protocol: &mut Protocol,
+    cfg: &mut CollateralConfig,
+    pos: &mut Position,
+    collateral_decimals: u8,
+) -> Result<(u64, u64)> {
+    let debt = pos.debt_pusd;
+    let (owed, shortfall) = settlement_collateral_owed(
+        debt,
+        pos.collateral_amount,
+        collateral_decimals,
+        cfg.final_price_6dp,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    pos.collateral_amount -= owed;
+    decrease_position_debt(pos, cfg, debt)?;
+    reindex_position(cfg, pos);
+    reduce_aggregate_debt(protocol, cfg, debt);
+    set_aside_settlement_collateral(cfg, owed, shortfall)?;
+    Ok((owed, shortfall))
+}
+
+/// Settle an auction's remaining debt target at the frozen price: the collateral covering it
+/// is set aside for PUSD holders and the rest returns to the position. Returns (collateral
+/// owed, uncovered debt, collateral returned).
+pub fn settle_auction_at_final_price(
+    protocol: &mut Protocol,
+    cfg: &mut CollateralConfig,
+    auction: &mut Auction,
+    pos: &mut Position,
+    collateral_decimals: u8,
+) -> Result<(u64, u64, u64)> {
+    let debt = auction.debt_remaining_pusd;
+    let (owed, shortfall) = settlement_collateral_owed(
+        debt,
+        auction.collateral_amount,
+        collateral_decimals,
+        cfg.final_price_6dp,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    let returned = auction.collateral_amount - owed;
+
+    pos.collateral_amount = pos
+        .collateral_amount
+        .checked_add(returned)
+        .ok_or(ErrorCode::MathOverflow)?;
+    reduce_aggregate_debt(protocol, cfg, debt);
+    set_aside_settlement_collateral(cfg, owed, shortfall)?;
+    cfg.active_auctions = cfg
+        .active_auctions
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    auction.collateral_amount = 0;
+    auction.debt_remaining_pusd = 0;
+    auction.active = false;
+    Ok((owed, shortfall, returned))
+}
+
+/// Add settled collateral and uncovered debt to the collateral's settlement totals.
+fn set_aside_settlement_collateral(
+    cfg: &mut CollateralConfig,
+    owed: u64,
+    shortfall: u64,
+) -> Result<()> {
+    cfg.settlement_collateral = cfg
+        .settlement_collateral
+        .checked_add(owed)
+        .ok_or(ErrorCode::MathOverflow)?;
+    cfg.settlement_shortfall_pusd = cfg
+        .settlement_shortfall_pusd
+        .checked_add(shortfall)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Move a position to the redemption bucket matching its collateral and normalized debt.
+/// Called after every change to either; debt-free positions leave the index.
+pub fn reindex_position(cfg: &mut CollateralConfig, pos: &mut Position) {
+    let bucket = if pos.normalized_debt == 0 {
+        REDEMPTION_BUCKET_NONE
+    } else {
+        redemption_bucket(compute_health_bps(
+            pos.collateral_amount as u128,
+            pos.normalized_debt,
+        ))
+    };
+    if bucket == pos.redemption_bucket {
+        return;
+    }
+
+    if pos.redemption_bucket != REDEMPTION_BUCKET_NONE {
+        let old = &mut cfg.redemption_buckets[pos.redemption_bucket as usize];
+        *old = old.saturating_sub(1);
+    }
+    if bucket != REDEMPTION_BUCKET_NONE {
+        let new = &mut cfg.redemption_buckets[bucket as usize];
+        *new = new.saturating_add(1);
+    }
+    pos.redemption_bucket = bucket;
+}
+
+/// Per-balance valuation of a cross-margin account, in balance order.
+pub struct CrossMarginValuation {
+    /// (value, LTV bps) per balance, for the health checks
+    pub legs: [(u128, u16); MAX_CROSS_MARGIN_COLLATERALS],
+    /// Price (6dp USD per whole token) each balance was valued at
+    pub prices_6dp: [u128; MAX_CROSS_MARGIN_COLLATERALS],
+    pub count: usize,
+}
+impl CrossMarginValuation {
+    pub fn legs(&self) -> &[(u128, u16)] {
+        &self.legs[..self.count]
+    }
+}
+
+/// Value every balance of a cross-margin account. `accounts` holds, for each balance in order:
+/// its CollateralConfig, its collateral mint, then its `price_account_count()` price accounts.
+/// Borrowing uses each collateral's borrow price and initial LTV; liquidation uses its
+/// liquidation price and maintenance LTV.
+pub fn value_cross_margin<'info>(
+    account: &CrossMarginAccount,
+    accounts: &'info [AccountInfo<'info>],
+    for_liquidation: bool,
+) -> Result<CrossMarginValuation> {
+    let mut valuation = CrossMarginValuation {
+        legs: [(0, 0); MAX_CROSS_MARGIN_COLLATERALS],
+        prices_6dp: [0; MAX_CROSS_MARGIN_COLLATERALS],
+        count: account.balance_count as usize,
+    };
+
+    let mut cursor = 0usize;
+    for (index, balance) in account.balances[..valuation.count].iter().enumerate() {
+        require!(accounts.len() >= cursor + 2, ErrorCode::UnsupportedCollateral);
+        let cfg_info = &accounts[cursor];
+        require_keys_eq!(
+            cfg_info.key(),
+            balance.collateral_config,
+            ErrorCode::UnsupportedCollateral
+        );
+        let cfg = Account::<CollateralConfig>::try_from(cfg_info)?;
+        let mint = InterfaceAccount::<InterfaceMint>::try_from(&accounts[cursor + 1])?;
+        require_keys_eq!(mint.key(), cfg.collateral_mint, ErrorCode::MintMismatch);
+
+        let price = collateral_price(&accounts[cursor + 2..], &cfg)?;
+        let (price_6dp, ltv_bps) = if for_liquidation {
+            (
+                price.liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode),
+                cfg.maintenance_ltv_bps,
+            )
+        } else {
+            (price.borrow_price_6dp(cfg.conf_multiplier_bps)?, cfg.initial_ltv_bps)
+        };
+        let value_6dp = token_amount_to_usd_6dp(balance.amount, mint.decimals, price_6dp)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        valuation.legs[index] = (value_6dp, ltv_bps);
+        valuation.prices_6dp[index] = price_6dp;
+        cursor += 2 + cfg.price_account_count();
+    }
+
+    Ok(valuation)
+}
+
+/// Refresh a cross-margin account's debt at the debt collateral's current index.
+pub fn sync_cross_margin_debt(
+    account: &mut CrossMarginAccount,
+    cfg: &CollateralConfig,
+    now_ts: i64,
+) -> Result<()> {
+    account.debt_pusd = normalized_to_debt(account.normalized_debt, cfg.rate_index)
+        .ok_or(ErrorCode::MathOverflow)?;
+    account.last_accrual_ts = now_ts;
+    Ok(())
+}
+
+/// Add newly minted debt to a cross-margin account (normalized amount rounded up).
+pub fn increase_cross_margin_debt(
+    account: &mut CrossMarginAccount,
+    cfg: &mut CollateralConfig,
+    amount: u64,
+) -> Result<()> {
+    let normalized =
+        debt_to_normalized(amount, cfg.rate_index, true).ok_or(ErrorCode::MathOverflow)?;
+    account.normalized_debt = account
+        .normalized_debt
+        .checked_add(normalized)
+        .ok_or(ErrorCode::MathOverflow)?;
+    cfg.total_normalized_debt = cfg
+        .total_normalized_debt
+        .checked_add(normalized)
+        .ok_or(ErrorCode::MathOverflow)?;
+    account.debt_pusd = normalized_to_debt(account.normalized_debt, cfg.rate_index)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove repaid debt from a cross-margin account (normalized amount rounded down).
+/// Repaying the full synced debt clears the normalized balance so no dust is left behind.
+pub fn decrease_cross_margin_debt(
+    account: &mut CrossMarginAccount,
+    cfg: &mut CollateralConfig,
+    amount: u64,
+) -> Result<()> {
+    require!(amount <= account.debt_pusd, ErrorCode::MathUnderflow);
+    let normalized = if amount == account.debt_pusd {
+        account.normalized_debt
+    } else {
+        debt_to_normalized(amount, cfg.rate_index, false)
+            .ok_or(ErrorCode::MathOverflow)?
+            .min(account.normalized_debt)
+    };
+    account.normalized_debt -= normalized;
+    cfg.total_normalized_debt = cfg.total_normalized_debt.saturating_sub(normalized);
+    account.debt_pusd = normalized_to_debt(account.normalized_debt, cfg.rate_index)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Cross-margin balances exhausted with debt left over: move it to bad debt.
+pub fn recognize_cross_margin_bad_debt(
+    protocol: &mut Account<Protocol>,
+    cfg: &mut CollateralConfig,
+    account: &mut CrossMarginAccount,
+) -> Result<()> {
+    if account.balance_count > 0 || account.debt_pusd == 0 {
+        return Ok(());
+    }
+
+    let residual = account.debt_pusd;
+    decrease_cross_margin_debt(account, cfg, residual)?;
+    reduce_aggregate_debt(protocol, cfg, residual);
+    protocol.bad_debt_pusd = protocol
+        .bad_debt_pusd
+        .checked_add(residual)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(BadDebtRecognized {
+        owner: account.owner,
+        collateral_mint: cfg.collateral_mint,
+        bad_debt_pusd_6dp: residual,
+        total_bad_debt_pusd_6dp: protocol.bad_debt_pusd,
+    });
+
+    Ok(())
+}
+
+/// Set aside the collateral of one cross-margin balance covering `uncovered` PUSD at its
+/// collateral's frozen price, leaving the part it could not cover in `uncovered`.
+/// Returns the collateral set aside.
+pub fn settle_cross_margin_balance(
+    cfg: &mut CollateralConfig,
+    collateral_mint: &InterfaceAccount<InterfaceMint>,
+    amount: u64,
+    uncovered: &mut u64,
+) -> Result<u64> {
+    require!(cfg.final_price_6dp > 0, ErrorCode::SettlementNotReady);
+    require_keys_eq!(collateral_mint.key(), cfg.collateral_mint, ErrorCode::MintMismatch);
+
+    let (owed, shortfall) = settlement_collateral_owed(
+        *uncovered,
+        amount,
+        collateral_mint.decimals,
+        cfg.final_price_6dp,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    cfg.settlement_collateral = cfg
+        .settlement_collateral
+        .checked_add(owed)
+        .ok_or(ErrorCode::MathOverflow)?;
+    *uncovered = shortfall;
+    Ok(owed)
+}
+
+/// Settle one cross-margin balance (see `settle_cross_margin_balance`) and drop it from the
+/// count its collateral keeps for `cash_collateral`. Balances with nothing left to cover skip
+/// the pricing, so debt-free accounts settle without their mints.
+pub fn settle_cross_margin_leg<'info>(
+    cfg: &mut CollateralConfig,
+    mint_info: &'info AccountInfo<'info>,
+    amount: u64,
+    uncovered: &mut u64,
+) -> Result<u64> {
+    let owed = if *uncovered > 0 {
+        let mint = InterfaceAccount::<InterfaceMint>::try_from(mint_info)?;
+        settle_cross_margin_balance(cfg, &mint, amount, uncovered)?
+    } else {
+        0
+    };
+    release_cross_margin_balance(cfg)?;
+    Ok(owed)
+}
+
+/// Stop counting a cross-margin balance against its collateral's `cash_collateral` gate.
+pub fn release_cross_margin_balance(cfg: &mut CollateralConfig) -> Result<()> {
+    cfg.cross_margin_balances = cfg
+        .cross_margin_balances
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    Ok(())
+}
+
+/// Riskiest non-empty redemption bucket, or REDEMPTION_BUCKET_NONE if no position has debt.
+pub fn lowest_redemption_bucket(cfg: &CollateralConfig) -> u8 {
+    cfg.redemption_buckets
+        .iter()
+        .position(|count| *count > 0)
+        .map_or(REDEMPTION_BUCKET_NONE, |bucket| bucket as u8)
+}
+
+/// Accrue the savings index up to `now_ts`, paying the interest from the treasury into the
+/// savings vault (signed by the Treasury PDA).
+pub fn accrue_savings<'info>(
+    vault: &mut Account<'info, SavingsVault>,
+    total_shares: u64,
+    treasury: &Account<'info, Treasury>,
+    treasury_pusd: &InterfaceAccount<'info, InterfaceTokenAccount>,
+    pusd_mint: &InterfaceAccount<'info, InterfaceMint>,
+    savings_pusd: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    now_ts: i64,
+) -> Result<()> {
+    let elapsed = now_ts.saturating_sub(vault.last_accrual_ts);
+    if elapsed <= 0 {
+        return Ok(());
+    }
+
+    let (new_index, interest) = accrue_savings_index(
+        vault.savings_index,
+        vault.savings_rate_bps,
+        elapsed,
+        total_shares,
+        treasury_pusd.amount,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    vault.savings_index = new_index;
+    vault.last_accrual_ts = now_ts;
+    if interest == 0 {
+        return Ok(());
+    }
+
+    let seeds = [
+        Treasury::SEED_PREFIX,
+        treasury.protocol.as_ref(),
+        &[treasury.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = token_interface::TransferChecked {
+        from: treasury_pusd.to_account_info(),
+        mint: pusd_mint.to_account_info(),
+        to: savings_pusd,
+        authority: treasury.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+    token_interface::transfer_checked(cpi_ctx, interest, pusd_mint.decimals)?;
+
+    vault.total_interest_pusd = vault
+        .total_interest_pusd
+        .checked_add(interest)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(SavingsAccrued {
+        protocol: vault.protocol,
+        savings_index: new_index,
+        interest_pusd_6dp: interest,
+        total_interest_pusd_6dp: vault.total_interest_pusd,
+    });
+
+    Ok(())
+}
+
+/// Savings index as of now, without moving any funds (for previews).
+pub fn preview_savings_index(ctx: &Context<PreviewSavings>) -> Result<u128> {
+    let vault = &ctx.accounts.savings_vault;
+    let elapsed = Clock::get()?
+        .unix_timestamp
+        .saturating_sub(vault.last_accrual_ts);
+    let (savings_index, _) = accrue_savings_index(
+        vault.savings_index,
+        vault.savings_rate_bps,
+        elapsed,
+        ctx.accounts.share_mint.supply,
+        ctx.accounts.treasury_pusd.amount,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    Ok(savings_index)
+}
+
+/// Validate and apply a collateral parameter update against the current config: LTV ordering
+/// and caps, per-update step sizes, and target health staying above maintenance.
+/// Emits `ParameterUpdated` for each field that changes.
+pub fn apply_collateral_params(
+    protocol: Pubkey,
+    cfg: &mut CollateralConfig,
+    params: &CollateralParamsUpdate,
+    now_ts: i64,
+) -> Result<()> {
+    let initial_ltv_bps = params.initial_ltv_bps.unwrap_or(cfg.initial_ltv_bps);
+    let maintenance_ltv_bps = params.maintenance_ltv_bps.unwrap_or(cfg.maintenance_ltv_bps);
+    let liq_bonus_bps = params.liq_bonus_bps.unwrap_or(cfg.liq_bonus_bps);
+    let debt_ceiling = params.debt_ceiling.unwrap_or(cfg.debt_ceiling);
+    let active = params.active.unwrap_or(cfg.active);
+
+    require!(
+        collateral_risk_params_valid(initial_ltv_bps, maintenance_ltv_bps, liq_bonus_bps),
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        cfg.target_health_bps == 0 || cfg.target_health_bps > maintenance_ltv_bps,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        within_step(
+            cfg.initial_ltv_bps as u64,
+            initial_ltv_bps as u64,
+            MAX_LTV_STEP_BPS as u64
+        ) && within_step(
+            cfg.maintenance_ltv_bps as u64,
+            maintenance_ltv_bps as u64,
+            MAX_LTV_STEP_BPS as u64
+        ) && within_step(
+            cfg.liq_bonus_bps as u64,
+            liq_bonus_bps as u64,
+            MAX_LIQ_BONUS_STEP_BPS as u64
+        ) && debt_ceiling_step_ok(cfg.debt_ceiling, debt_ceiling),
+        ErrorCode::ParameterStepTooLarge
+    );
+
+    // Steps are measured from the current config, so space them out; cutting the ceiling and
+    // toggling `active` stay immediate
+    let stepped = initial_ltv_bps != cfg.initial_ltv_bps
+        || maintenance_ltv_bps != cfg.maintenance_ltv_bps
+        || liq_bonus_bps != cfg.liq_bonus_bps
+        || debt_ceiling > cfg.debt_ceiling;
+    if stepped {
+        require!(
+            risk_update_due(cfg.last_risk_update_ts, now_ts),
+            ErrorCode::RiskUpdateTooSoon
+        );
+        cfg.last_risk_update_ts = now_ts;
+    }
+
+    if initial_ltv_bps != cfg.initial_ltv_bps {
+        emit_parameter_updated(
+            protocol,
+            "initial_ltv_bps",
+            cfg.initial_ltv_bps as u64,
+            initial_ltv_bps as u64,
+        );
+        cfg.initial_ltv_bps = initial_ltv_bps;
+    }
+    if maintenance_ltv_bps != cfg.maintenance_ltv_bps {
+        emit_parameter_updated(
+            protocol,
+            "maintenance_ltv_bps",
+            cfg.maintenance_ltv_bps as u64,
+            maintenance_ltv_bps as u64,
+        );
+        cfg.maintenance_ltv_bps = maintenance_ltv_bps;
+    }
+    if liq_bonus_bps != cfg.liq_bonus_bps {
+        emit_parameter_updated(
+            protocol,
+            "liq_bonus_bps",
+            cfg.liq_bonus_bps as u64,
+            liq_bonus_bps as u64,
+        );
+        cfg.liq_bonus_bps = liq_bonus_bps;
+    }
+    if debt_ceiling != cfg.debt_ceiling {
+        emit_parameter_updated(protocol, "debt_ceiling", cfg.debt_ceiling, debt_ceiling);
+        cfg.debt_ceiling = debt_ceiling;
+    }
+    if active != cfg.active {
+        emit_parameter_updated(protocol, "active", cfg.active as u64, active as u64);
+        cfg.active = active;
+    }
+
+    Ok(())
+}
+
+/// Apply a bounds-checked PSM parameter update, emitting `ParameterUpdated` per changed field.
+pub fn apply_psm_params(protocol: Pubkey, psm: &mut PsmConfig, params: &PsmParamsUpdate) {
+    if let Some(fee_in_bps) = params.fee_in_bps.filter(|fee| *fee != psm.fee_in_bps) {
+        emit_parameter_updated(protocol, "psm_fee_in_bps", psm.fee_in_bps as u64, fee_in_bps as u64);
+        psm.fee_in_bps = fee_in_bps;
+    }
+    if let Some(fee_out_bps) = params.fee_out_bps.filter(|fee| *fee != psm.fee_out_bps) {
+        emit_parameter_updated(protocol, "psm_fee_out_bps", psm.fee_out_bps as u64, fee_out_bps as u64);
+        psm.fee_out_bps = fee_out_bps;
+    }
+    if let Some(debt_ceiling) = params.debt_ceiling.filter(|ceiling| *ceiling != psm.debt_ceiling) {
+        emit_parameter_updated(protocol, "psm_debt_ceiling", psm.debt_ceiling, debt_ceiling);
+        psm.debt_ceiling = debt_ceiling;
+    }
+    if let Some(active) = params.active.filter(|active| *active != psm.active) {
+        emit_parameter_updated(protocol, "psm_active", psm.active as u64, active as u64);
+        psm.active = active;
+    }
+}
+
+/// Emit `ParameterUpdated` for one field.
+pub fn emit_parameter_updated(protocol: Pubkey, field: &str, old_value: u64, new_value: u64) {
+    emit!(ParameterUpdated {
+        protocol,
+        field: pack_label(field),
+        old_value,
+        new_value,
+    });
+}
+
+/// ===============================
+/// Accounts
+/// ===============================
+
+#[derive(Accounts)]
+#[instruction(global_debt_ceiling: u64)]
+pub struct Initialize<'info> {
+    /// Existing PUSD mint under either token program. Its keypair signs: the Protocol PDA is
+    /// seeded by the mint alone, so otherwise anyone could front-run the deployer as root admin.
+    #[account(mut, signer)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// CHECK: PDA derived inside program; stored & used as signer for minting.
+    /// Seeded by the PUSD mint so the admin can be rotated without a new mint.
+    #[account(
+        init,
+        payer = authority,
+        space = Protocol::LEN,
+        seeds = [Protocol::SEED_PREFIX, pusd_mint.key().as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System / SPL
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(global_debt_ceiling: u64)]
+pub struct InitializeToken2022<'info> {
+    /// New PUSD mint keypair; created and initialized here under Token-2022
+    #[account(mut)]
+    pub pusd_mint: Signer<'info>,
+
+    /// CHECK: PDA derived inside program; stored & used as signer for minting.
+    #[account(
+        init,
+        payer = authority,
+        space = Protocol::LEN,
+        seeds = [Protocol::SEED_PREFIX, pusd_mint.key().as_ref()],
+        bump
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct AddCollateral<'info> {
+    #[account(mut, has_one = risk_admin @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub risk_admin: Signer<'info>,
+
+    /// Collateral mint we are registering
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Collateral vault (SPL token account) owned by program PDA
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// New PDA to store config for this collateral
+    #[account(
+        init,
+        payer = risk_admin,
+        space = CollateralConfig::LEN,
+        seeds = [
+            CollateralConfig::SEED_PREFIX,
+            protocol.key().as_ref(),
+            collateral_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct OpenOrFundPosition<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.collateral_mint == collateral_mint.key() @ ErrorCode::MintMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// User's token account for the collateral they are depositing
+    #[account(mut)]
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Protocol vault for the collateral (must match config)
+    #[account(mut, constraint = vault.key() == collateral_config.vault @ ErrorCode::VaultMismatch)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Position PDA for this (owner, collateral_mint)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Position::LEN,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_mint.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MintPusd<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BurnPusd<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCollateral<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    /// Owner receives the withdrawn collateral here
+    #[account(mut)]
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    /// Liquidator burns their PUSD to repay debt
+    #[account(mut)]
+    pub liquidator_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Liquidator receives seized collateral here
+    #[account(mut)]
+    pub liquidator_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// The owner of the liquidated position (read-only for seeds)
+    /// CHECK: provided to derive the position PDA seed above
+    pub owner: UncheckedAccount<'info>,
+
+    pub liquidator: Signer<'info>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct StartAuction<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    /// Auction PDA for this position (reused across liquidations)
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = Auction::LEN,
+        seeds = [Auction::SEED_PREFIX, position.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The owner of the liquidated position (read-only for seeds)
+    /// CHECK: provided to derive the position PDA seed above
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedoAuction<'info> {
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        mut,
+        seeds = [Auction::SEED_PREFIX, position.key().as_ref()],
+        bump = auction.bump,
+        has_one = collateral_config @ ErrorCode::Unauthorized,
+        has_one = position @ ErrorCode::Unauthorized
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// Auctioned position (seed for the auction PDA)
+    pub position: Account<'info, Position>,
+
+    pub keeper: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TakeAuction<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for pricing
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [Auction::SEED_PREFIX, position.key().as_ref()],
+        bump = auction.bump,
+        has_one = collateral_config @ ErrorCode::Unauthorized,
+        has_one = position @ ErrorCode::Unauthorized
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// Auctioned position; receives leftover collateral on settlement
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+
+    /// Keeper burns their PUSD to pay for collateral
+    #[account(mut)]
+    pub keeper_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Keeper receives bought collateral here
+    #[account(mut)]
+    pub keeper_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub keeper: Signer<'info>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStabilityPool<'info> {
+    #[account(has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = StabilityPool::LEN,
+        seeds = [StabilityPool::SEED_PREFIX, collateral_config.key().as_ref()],
+        bump
+    )]
+    pub stability_pool: Account<'info, StabilityPool>,
+
+    /// Pool PUSD account (PDA, owned by the pool)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [StabilityPool::PUSD_ACCOUNT_SEED_PREFIX, stability_pool.key().as_ref()],
+        bump,
+        token::mint = pusd_mint,
+        token::authority = stability_pool
+    )]
+    pub stability_pool_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ProvideToStabilityPool<'info> {
+    #[account(
+        mut,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// PUSD mint; `transfer_checked` needs its decimals
+    #[account(constraint = pusd_mint.key() == stability_pool_pusd.mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [StabilityPool::SEED_PREFIX, collateral_config.key().as_ref()],
+        bump = stability_pool.bump
+    )]
+    pub stability_pool: Account<'info, StabilityPool>,
+
+    #[account(mut, constraint = stability_pool_pusd.key() == stability_pool.pusd_account @ ErrorCode::VaultMismatch)]
+    pub stability_pool_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = StabilityDeposit::LEN,
+        seeds = [StabilityDeposit::SEED_PREFIX, stability_pool.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub deposit: Account<'info, StabilityDeposit>,
+
+    /// Depositor's PUSD source
+    #[account(mut)]
+    pub depositor_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Depositor receives collateral gains here
+    #[account(mut)]
+    pub depositor_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromStabilityPool<'info> {
+    #[account(
+        mut,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// PUSD mint; `transfer_checked` needs its decimals
+    #[account(constraint = pusd_mint.key() == stability_pool_pusd.mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [StabilityPool::SEED_PREFIX, collateral_config.key().as_ref()],
+        bump = stability_pool.bump
+    )]
+    pub stability_pool: Account<'info, StabilityPool>,
+
+    #[account(mut, constraint = stability_pool_pusd.key() == stability_pool.pusd_account @ ErrorCode::VaultMismatch)]
+    pub stability_pool_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [StabilityDeposit::SEED_PREFIX, stability_pool.key().as_ref(), depositor.key().as_ref()],
+        bump = deposit.bump
+    )]
+    pub deposit: Account<'info, StabilityDeposit>,
+
+    /// Depositor receives withdrawn PUSD here
+    #[account(mut)]
+    pub depositor_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Depositor receives collateral gains here
+    #[account(mut)]
+    pub depositor_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub depositor: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct LiquidateWithStabilityPool<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        seeds = [StabilityPool::SEED_PREFIX, collateral_config.key().as_ref()],
+        bump = stability_pool.bump
+    )]
+    pub stability_pool: Account<'info, StabilityPool>,
+
+    #[account(mut, constraint = stability_pool_pusd.key() == stability_pool.pusd_account @ ErrorCode::VaultMismatch)]
+    pub stability_pool_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// The owner of the liquidated position (read-only for seeds)
+    /// CHECK: provided to derive the position PDA seed above
+    pub owner: UncheckedAccount<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AddPsmAsset<'info> {
+    #[account(has_one = risk_admin @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    pub stable_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        init,
+        payer = risk_admin,
+        space = PsmConfig::LEN,
+        seeds = [PsmConfig::SEED_PREFIX, protocol.key().as_ref(), stable_mint.key().as_ref()],
+        bump
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+
+    /// PSM vault (PDA, owned by the PSM config)
+    #[account(
+        init,
+        payer = risk_admin,
+        seeds = [PsmConfig::VAULT_SEED_PREFIX, psm_config.key().as_ref()],
+        bump,
+        token::mint = stable_mint,
+        token::authority = psm_config
+    )]
+    pub psm_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub risk_admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning the stable mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PsmSwapIn<'info> {
+    #[account(mut, has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        has_one = stable_mint @ ErrorCode::MintMismatch,
+        constraint = psm_config.vault == psm_vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+
+    pub stable_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub psm_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub user_stable_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub user: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the stable mint (SPL Token or Token-2022)
+    pub stable_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PsmSwapOut<'info> {
+    #[account(mut, has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        has_one = stable_mint @ ErrorCode::MintMismatch,
+        constraint = psm_config.vault == psm_vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub psm_config: Account<'info, PsmConfig>,
+
+    pub stable_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub psm_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub user_stable_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub user: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the stable mint (SPL Token or Token-2022)
+    pub stable_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut, has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Redeemer burns their PUSD here
+    #[account(mut)]
+    pub redeemer_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Redeemer receives collateral here
+    #[account(mut)]
+    pub redeemer_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub redeemer: Signer<'info>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, has_one = pending_authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocol<'info> {
+    /// CHECK: legacy-layout Protocol; owner, discriminator and PDA are verified in the handler
+    #[account(mut)]
+    pub protocol: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TogglePause<'info> {
+    #[account(mut, has_one = guardian @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ToggleCollateralPause<'info> {
+    #[account(has_one = guardian @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Accrue<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = pusd_mint @ ErrorCode::MintMismatch
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [Treasury::SEED_PREFIX, protocol.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury PUSD account (PDA, owned by the treasury)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [Treasury::PUSD_ACCOUNT_SEED_PREFIX, protocol.key().as_ref()],
+        bump,
+        token::mint = pusd_mint,
+        token::authority = treasury
+    )]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Where governance sweeps surplus (any PUSD token account)
+    #[account(constraint = surplus_destination.mint == pusd_mint.key() @ ErrorCode::MintMismatch)]
+    pub surplus_destination: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(mut, has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SweepSurplus<'info> {
+    #[account(
+        has_one = treasury_authority @ ErrorCode::Unauthorized,
+        has_one = pusd_mint @ ErrorCode::MintMismatch
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        constraint = surplus_destination.key() == treasury.surplus_destination @ ErrorCode::TreasuryMismatch
+    )]
+    pub surplus_destination: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub treasury_authority: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CoverBadDebt<'info> {
+    #[account(
+        mut,
+        has_one = treasury_authority @ ErrorCode::Unauthorized,
+        has_one = pusd_mint @ ErrorCode::MintMismatch
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub treasury_authority: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSavings<'info> {
+    #[account(
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = pusd_mint @ ErrorCode::MintMismatch
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SavingsVault::LEN,
+        seeds = [SavingsVault::SEED_PREFIX, protocol.key().as_ref()],
+        bump
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+
+    /// sPUSD share mint (PDA, minted by the savings vault)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SavingsVault::SHARE_MINT_SEED_PREFIX, protocol.key().as_ref()],
+        bump,
+        mint::decimals = PUSD_DECIMALS as u8,
+        mint::authority = savings_vault
+    )]
+    pub share_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Savings PUSD account (PDA, owned by the savings vault)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [SavingsVault::PUSD_ACCOUNT_SEED_PREFIX, protocol.key().as_ref()],
+        bump,
+        token::mint = pusd_mint,
+        token::authority = savings_vault
+    )]
+    pub savings_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SavingsDeposit<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ ErrorCode::MintMismatch,
+        constraint = savings_vault.pusd_account == savings_pusd.key() @ ErrorCode::VaultMismatch
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub savings_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// PUSD mint; `transfer_checked` needs its decimals
+    #[account(constraint = pusd_mint.key() == savings_pusd.mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(constraint = treasury.protocol == savings_vault.protocol @ ErrorCode::TreasuryMismatch)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Depositor's PUSD source
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Depositor receives sPUSD here
+    #[account(mut)]
+    pub user_share_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SavingsWithdraw<'info> {
+    #[account(
+        mut,
+        has_one = share_mint @ ErrorCode::MintMismatch,
+        constraint = savings_vault.pusd_account == savings_pusd.key() @ ErrorCode::VaultMismatch
+    )]
+    pub savings_vault: Account<'info, SavingsVault>,
+
+    #[account(mut)]
+    pub share_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub savings_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// PUSD mint; `transfer_checked` needs its decimals
+    #[account(constraint = pusd_mint.key() == savings_pusd.mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(constraint = treasury.protocol == savings_vault.protocol @ ErrorCode::TreasuryMismatch)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// sPUSD burned from here
+    #[account(mut)]
+    pub user_share_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Withdrawn PUSD is paid here
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewSavings<'info> {
+    #[account(has_one = share_mint @ ErrorCode::MintMismatch)]
+    pub savings_vault: Account<'info, SavingsVault>,
+
+    pub share_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(constraint = treasury.protocol == savings_vault.protocol @ ErrorCode::TreasuryMismatch)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Governance::LEN,
+        seeds = [Governance::SEED_PREFIX, protocol.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct QueueChange<'info> {
+    #[account(has_one = risk_admin @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = protocol.governance == governance.key() @ ErrorCode::Unauthorized
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        payer = risk_admin,
+        space = Proposal::LEN,
+        seeds = [Proposal::SEED_PREFIX, governance.key().as_ref(), &proposal_id.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub risk_admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = protocol.governance == governance.key() @ ErrorCode::Unauthorized
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        has_one = governance @ ErrorCode::Unauthorized,
+        has_one = proposer @ ErrorCode::Unauthorized,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; must match `proposal.proposer`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// Collateral-level changes
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Option<Account<'info, CollateralConfig>>,
+
+    /// PSM asset changes
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub psm_config: Option<Account<'info, PsmConfig>>,
+
+    /// Savings rate changes (the vault accrues before the rate moves)
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub savings_vault: Option<Account<'info, SavingsVault>>,
+
+    pub share_mint: Option<InterfaceAccount<'info, InterfaceMint>>,
+
+    #[account(mut)]
+    pub savings_pusd: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    #[account(mut)]
+    pub treasury_pusd: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    #[account(constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: Option<InterfaceAccount<'info, InterfaceMint>>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    #[account(has_one = risk_admin @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        has_one = governance @ ErrorCode::Unauthorized,
+        has_one = proposer @ ErrorCode::Unauthorized,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: receives the proposal rent; must match `proposal.proposer`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub risk_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Cage<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Settlement::LEN,
+        seeds = [Settlement::SEED_PREFIX, protocol.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CageCollateral<'info> {
+    #[account(mut, has_one = authority @ ErrorCode::Unauthorized)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettlePosition<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut, has_one = collateral_config @ ErrorCode::UnsupportedCollateral)]
+    pub position: Account<'info, Position>,
+}
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
     #[account(mut)]
     pub protocol: Account<'info, Protocol>,
 
-    pub collateral_mint: Account<'info, Mint>,
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    /// Must match the collateral config; supplies token decimals for valuation
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [Auction::SEED_PREFIX, position.key().as_ref()],
+        bump = auction.bump,
+        has_one = collateral_config @ ErrorCode::Unauthorized,
+        has_one = position @ ErrorCode::Unauthorized
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// Auctioned position; receives the collateral not set aside
+    #[account(mut)]
+    pub position: Account<'info, Position>,
+}
+
+#[derive(Accounts)]
+pub struct FreeCollateral<'info> {
+    pub protocol: Account<'info, Protocol>,
 
     #[account(
+        mut,
         has_one = protocol @ ErrorCode::Unauthorized,
-        constraint = collateral_config.collateral_mint == collateral_mint.key() @ ErrorCode::MintMismatch
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
     )]
     pub collateral_config: Account<'info, CollateralConfig>,
 
-    /// User's token account for the collateral they are depositing
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
     #[account(mut)]
-    pub user_collateral_ata: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
 
-    /// Protocol vault for the collateral (must match config)
-    #[account(mut, constraint = vault.key() == collateral_config.vault @ ErrorCode::VaultMismatch)]
-    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, Position>,
+
+    /// Owner receives the freed collateral here
+    #[account(mut)]
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Thaw<'info> {
+    #[account(mut, has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [Settlement::SEED_PREFIX, protocol.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    #[account(
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [Treasury::SEED_PREFIX, protocol.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Treasury PUSD, burned before the supply is fixed
+    #[account(mut, constraint = treasury_pusd.key() == treasury.pusd_account @ ErrorCode::TreasuryMismatch)]
+    pub treasury_pusd: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PackPusd<'info> {
+    #[account(has_one = pusd_mint @ ErrorCode::MintMismatch)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [Settlement::SEED_PREFIX, protocol.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
 
-    /// Position PDA for this (owner, collateral_mint)
     #[account(
         init_if_needed,
         payer = owner,
-        space = Position::LEN,
-        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_mint.key().as_ref()],
+        space = SettlementClaim::LEN,
+        seeds = [SettlementClaim::SEED_PREFIX, settlement.key().as_ref(), owner.key().as_ref()],
         bump
     )]
-    pub position: Account<'info, Position>,
+    pub claim: Account<'info, SettlementClaim>,
+
+    /// Holder burns their PUSD here
+    #[account(mut)]
+    pub owner_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
 
     #[account(mut)]
     pub owner: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct MintPusd<'info> {
+pub struct CashCollateral<'info> {
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [Settlement::SEED_PREFIX, protocol.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        has_one = settlement @ ErrorCode::Unauthorized,
+        has_one = owner @ ErrorCode::Unauthorized
+    )]
+    pub claim: Account<'info, SettlementClaim>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SettlementCash::LEN,
+        seeds = [
+            SettlementCash::SEED_PREFIX,
+            claim.key().as_ref(),
+            collateral_config.key().as_ref()
+        ],
+        bump
+    )]
+    pub cash: Account<'info, SettlementCash>,
+
+    /// Holder receives collateral here
+    #[account(mut)]
+    pub owner_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
     #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct OpenCrossMargin<'info> {
     pub protocol: Account<'info, Protocol>,
 
+    /// Collateral whose rate index, fees and debt ceiling the account's debt is booked under
+    #[account(has_one = protocol @ ErrorCode::Unauthorized)]
+    pub debt_collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = CrossMarginAccount::LEN,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
+
     #[account(mut)]
-    pub pusd_mint: Account<'info, Mint>,
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositCrossMargin<'info> {
+    pub protocol: Account<'info, Protocol>,
 
     #[account(
+        mut,
         has_one = protocol @ ErrorCode::Unauthorized,
         constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
     )]
     pub collateral_config: Account<'info, CollateralConfig>,
 
+    /// Must match the collateral config; supplies token decimals for the transfer
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
     #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
 
     #[account(
         mut,
-        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
-        bump = position.bump
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
     )]
-    pub position: Account<'info, Position>,
+    pub cross_margin: Account<'info, CrossMarginAccount>,
+
+    /// Owner deposits collateral from here
+    #[account(mut)]
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MintCrossMargin<'info> {
+    #[account(mut)]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub debt_collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        mut,
+        has_one = debt_collateral_config @ ErrorCode::UnsupportedCollateral,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
 
     #[account(mut)]
-    pub user_pusd_ata: Account<'info, TokenAccount>,
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
 
     pub owner: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct BurnPusd<'info> {
+pub struct BurnCrossMargin<'info> {
     #[account(mut)]
     pub protocol: Account<'info, Protocol>,
 
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub debt_collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        mut,
+        has_one = debt_collateral_config @ ErrorCode::UnsupportedCollateral,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
+
+    #[account(mut)]
+    pub user_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawCrossMargin<'info> {
     #[account(mut)]
-    pub pusd_mint: Account<'info, Mint>,
+    pub protocol: Account<'info, Protocol>,
 
+    /// Collateral being withdrawn
     #[account(
-        has_one = protocol @ ErrorCode::Unauthorized
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
     )]
     pub collateral_config: Account<'info, CollateralConfig>,
 
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Collateral the account's debt is booked under; omitted when that is `collateral_config`,
+    /// so no config is loaded (and written back) twice
     #[account(
         mut,
-        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
-        bump = position.bump
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = debt_collateral_config.key() != collateral_config.key()
+            @ ErrorCode::UnsupportedCollateral
     )]
-    pub position: Account<'info, Position>,
+    pub debt_collateral_config: Option<Account<'info, CollateralConfig>>,
+
+    #[account(
+        mut,
+        constraint = cross_margin.debt_collateral_config
+            == debt_collateral_config.as_ref().map_or(collateral_config.key(), |cfg| cfg.key())
+            @ ErrorCode::UnsupportedCollateral,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
 
+    /// Owner receives the withdrawn collateral here
     #[account(mut)]
-    pub user_pusd_ata: Account<'info, TokenAccount>,
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
 
     pub owner: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct Liquidate<'info> {
+pub struct LiquidateCrossMargin<'info> {
     #[account(mut)]
     pub protocol: Account<'info, Protocol>,
 
-    #[account(mut)]
-    pub pusd_mint: Account<'info, Mint>,
+    #[account(mut, constraint = pusd_mint.key() == protocol.pusd_mint @ ErrorCode::MintMismatch)]
+    pub pusd_mint: InterfaceAccount<'info, InterfaceMint>,
 
+    /// Collateral being seized
     #[account(
+        mut,
         has_one = protocol @ ErrorCode::Unauthorized,
         constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
     )]
     pub collateral_config: Account<'info, CollateralConfig>,
 
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
     #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
 
+    /// Collateral the account's debt is booked under; omitted when that is `collateral_config`
     #[account(
         mut,
-        seeds = [Position::SEED_PREFIX, owner.key().as_ref(), collateral_config.collateral_mint.as_ref()],
-        bump = position.bump
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = debt_collateral_config.key() != collateral_config.key()
+            @ ErrorCode::UnsupportedCollateral
     )]
-    pub position: Account<'info, Position>,
+    pub debt_collateral_config: Option<Account<'info, CollateralConfig>>,
+
+    #[account(
+        mut,
+        constraint = cross_margin.debt_collateral_config
+            == debt_collateral_config.as_ref().map_or(collateral_config.key(), |cfg| cfg.key())
+            @ ErrorCode::UnsupportedCollateral,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
 
     /// Liquidator burns their PUSD to repay debt
     #[account(mut)]
-    pub liquidator_pusd_ata: Account<'info, TokenAccount>,
+    pub liquidator_pusd_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
 
     /// Liquidator receives seized collateral here
     #[account(mut)]
-    pub liquidator_collateral_ata: Account<'info, TokenAccount>,
+    pub liquidator_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
 
-    /// The owner of the liquidated position (read-only for seeds)
-    /// CHECK: provided to derive the position PDA seed above
+    /// The owner of the liquidated account (read-only for seeds)
+    /// CHECK: provided to derive the cross-margin PDA seed above
     pub owner: UncheckedAccount<'info>,
 
     pub liquidator: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    /// Token program owning the PUSD mint (SPL Token or Token-2022)
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning the collateral mint (SPL Token or Token-2022)
+    pub collateral_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct TogglePause<'info> {
+pub struct SettleCrossMargin<'info> {
     #[account(mut)]
     pub protocol: Account<'info, Protocol>,
-    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = protocol @ ErrorCode::Unauthorized)]
+    pub debt_collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(
+        mut,
+        has_one = debt_collateral_config @ ErrorCode::UnsupportedCollateral,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
+
+    /// The owner of the settled account (read-only for seeds)
+    /// CHECK: provided to derive the cross-margin PDA seed above
+    pub owner: UncheckedAccount<'info>,
 }
 
+#[derive(Accounts)]
+pub struct FreeCrossMarginCollateral<'info> {
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        constraint = collateral_config.vault == vault.key() @ ErrorCode::VaultMismatch
+    )]
+    pub collateral_config: Account<'info, CollateralConfig>,
+
+    #[account(constraint = collateral_mint.key() == collateral_config.collateral_mint @ ErrorCode::MintMismatch)]
+    pub collateral_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        has_one = protocol @ ErrorCode::Unauthorized,
+        seeds = [CrossMarginAccount::SEED_PREFIX, owner.key().as_ref()],
+        bump = cross_margin.bump
+    )]
+    pub cross_margin: Account<'info, CrossMarginAccount>,
+
+    /// Owner receives the freed collateral here
+    #[account(mut)]
+    pub user_collateral_ata: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
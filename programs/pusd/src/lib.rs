@@ -3,12 +3,37 @@ use anchor_lang::prelude::*;
 pub mod accounts;
 pub mod errors;
 pub mod instructions;
+pub mod oracle;
 pub mod state;
 
+use crate::state::{CollateralRiskParams, GovernanceAction, OracleParams, ProtocolRole};
 use crate::instructions::{
-    handle_add_collateral, handle_burn, handle_initialize, handle_liquidate, handle_mint,
-    handle_open_or_fund_position, handle_toggle_pause, AddCollateral, BurnPusd, Initialize,
-    Liquidate, MintPusd, OpenOrFundPosition, TogglePause,
+    handle_accept_authority, handle_accrue, handle_add_collateral, handle_add_psm_asset,
+    handle_burn, handle_burn_cross_margin, handle_cage, handle_cage_collateral,
+    handle_cancel_change, handle_cash_collateral, handle_collect_fees, handle_cover_bad_debt,
+    handle_deposit_cross_margin, handle_execute_change, handle_free_collateral,
+    handle_free_cross_margin_collateral, handle_initialize, handle_initialize_governance,
+    handle_initialize_savings, handle_initialize_stability_pool, handle_initialize_token_2022,
+    handle_initialize_treasury, handle_liquidate, handle_liquidate_cross_margin,
+    handle_liquidate_with_stability_pool, handle_migrate_protocol, handle_mint,
+    handle_mint_cross_margin, handle_open_cross_margin, handle_open_or_fund_position,
+    handle_pack_pusd, handle_preview_savings_deposit, handle_preview_savings_withdraw,
+    handle_propose_authority, handle_provide_to_stability_pool, handle_psm_swap_in,
+    handle_psm_swap_out, handle_queue_change, handle_redeem, handle_redo_auction,
+    handle_savings_deposit, handle_savings_withdraw, handle_set_role, handle_settle_auction,
+    handle_settle_cross_margin, handle_settle_position, handle_start_auction, handle_sweep_surplus,
+    handle_take_auction, handle_thaw, handle_toggle_collateral_pause, handle_toggle_pause,
+    handle_withdraw_collateral, handle_withdraw_cross_margin, handle_withdraw_from_stability_pool,
+    AcceptAuthority, Accrue, AddCollateral, AddPsmAsset, BurnCrossMargin, BurnPusd, Cage,
+    CageCollateral, CancelChange, CashCollateral, CollectFees, CoverBadDebt, DepositCrossMargin,
+    ExecuteChange, FreeCollateral, FreeCrossMarginCollateral, Initialize, InitializeGovernance,
+    InitializeSavings, InitializeStabilityPool, InitializeToken2022, InitializeTreasury, Liquidate,
+    LiquidateCrossMargin, LiquidateWithStabilityPool, MigrateProtocol, MintCrossMargin, MintPusd,
+    OpenCrossMargin, OpenOrFundPosition, PackPusd, PreviewSavings, ProposeAuthority,
+    ProvideToStabilityPool, PsmSwapIn, PsmSwapOut, QueueChange, Redeem, RedoAuction, SavingsDeposit,
+    SavingsWithdraw, SetRole, SettleAuction, SettleCrossMargin, SettlePosition, StartAuction,
+    SweepSurplus, TakeAuction, Thaw, ToggleCollateralPause, TogglePause, WithdrawCollateral,
+    WithdrawCrossMargin, WithdrawFromStabilityPool,
 };
 
 declare_id!("PUSD111111111111111111111111111111111111111");
@@ -22,23 +47,26 @@ pub mod pusd {
         handle_initialize(ctx, global_debt_ceiling)
     }
 
-    /// Register a collateral type and its vault.
+    /// Initialize the protocol with a new Token-2022 PUSD mint carrying on-chain metadata.
+    pub fn initialize_token_2022(
+        ctx: Context<InitializeToken2022>,
+        global_debt_ceiling: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        handle_initialize_token_2022(ctx, global_debt_ceiling, name, symbol, uri)
+    }
+
+    /// Register a collateral type, its vault and up to three oracle sources
+    /// (oracle accounts passed as remaining accounts).
     pub fn add_collateral(
         ctx: Context<AddCollateral>,
-        initial_ltv_bps: u16,
-        maintenance_ltv_bps: u16,
-        liq_bonus_bps: u16,
-        debt_ceiling: u64,
+        risk_params: CollateralRiskParams,
+        oracle_params: OracleParams,
         active: bool,
     ) -> Result<()> {
-        handle_add_collateral(
-            ctx,
-            initial_ltv_bps,
-            maintenance_ltv_bps,
-            liq_bonus_bps,
-            debt_ceiling,
-            active,
-        )
+        handle_add_collateral(ctx, risk_params, oracle_params, active)
     }
 
     /// Create (if needed) or fund a position by depositing collateral.
@@ -49,46 +77,294 @@ pub mod pusd {
         handle_open_or_fund_position(ctx, deposit_amount)
     }
 
-    /// Mint PUSD against deposited collateral.
-    /// TEMPORARY: price & decimals passed in until oracles are wired.
-    pub fn mint_pusd(
-        ctx: Context<MintPusd>,
-        mint_pusd_6dp: u64,
-        collateral_price_usd_6dp: u128,
-        collateral_decimals: u8,
-    ) -> Result<()> {
-        handle_mint(
-            ctx,
-            mint_pusd_6dp,
-            collateral_price_usd_6dp,
-            collateral_decimals,
-        )
+    /// Mint PUSD against deposited collateral (priced via the registered oracles).
+    pub fn mint_pusd(ctx: Context<MintPusd>, mint_pusd_6dp: u64) -> Result<()> {
+        handle_mint(ctx, mint_pusd_6dp)
     }
 
-    /// Burn/repay PUSD (withdrawal can be a separate instruction).
+    /// Burn/repay PUSD.
     pub fn burn_pusd(ctx: Context<BurnPusd>, burn_pusd_6dp: u64) -> Result<()> {
         handle_burn(ctx, burn_pusd_6dp)
     }
 
-    /// Liquidate an unhealthy position.
-    /// TEMPORARY: price & decimals passed in until oracles are wired.
-    pub fn liquidate(
-        ctx: Context<Liquidate>,
+    /// Withdraw collateral; the remainder must stay within initial LTV.
+    pub fn withdraw_collateral(
+        ctx: Context<WithdrawCollateral>,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        handle_withdraw_collateral(ctx, withdraw_amount)
+    }
+
+    /// Liquidate an unhealthy position (priced via the registered oracles).
+    pub fn liquidate(ctx: Context<Liquidate>, repay_pusd_6dp: u64) -> Result<()> {
+        handle_liquidate(ctx, repay_pusd_6dp)
+    }
+
+    /// Permissionless: move an unhealthy position into a Dutch auction.
+    pub fn start_auction(ctx: Context<StartAuction>) -> Result<()> {
+        handle_start_auction(ctx)
+    }
+
+    /// Permissionless: restart an expired auction at a fresh oracle price.
+    pub fn redo_auction(ctx: Context<RedoAuction>) -> Result<()> {
+        handle_redo_auction(ctx)
+    }
+
+    /// Buy collateral from a running auction with PUSD.
+    pub fn take_auction(
+        ctx: Context<TakeAuction>,
+        max_collateral: u64,
+        max_price_6dp: u64,
+    ) -> Result<()> {
+        handle_take_auction(ctx, max_collateral, max_price_6dp)
+    }
+
+    /// Create the stability pool for a collateral type.
+    pub fn initialize_stability_pool(ctx: Context<InitializeStabilityPool>) -> Result<()> {
+        handle_initialize_stability_pool(ctx)
+    }
+
+    /// Deposit PUSD into a stability pool.
+    pub fn provide_to_stability_pool(ctx: Context<ProvideToStabilityPool>, amount: u64) -> Result<()> {
+        handle_provide_to_stability_pool(ctx, amount)
+    }
+
+    /// Withdraw PUSD from a stability pool and claim collateral gains (amount 0 only claims).
+    pub fn withdraw_from_stability_pool(
+        ctx: Context<WithdrawFromStabilityPool>,
+        amount: u64,
+    ) -> Result<()> {
+        handle_withdraw_from_stability_pool(ctx, amount)
+    }
+
+    /// Permissionless: liquidate an unhealthy position against the stability pool.
+    pub fn liquidate_with_stability_pool(ctx: Context<LiquidateWithStabilityPool>) -> Result<()> {
+        handle_liquidate_with_stability_pool(ctx)
+    }
+
+    /// Approve a stablecoin for the Peg Stability Module.
+    pub fn add_psm_asset(
+        ctx: Context<AddPsmAsset>,
+        fee_in_bps: u16,
+        fee_out_bps: u16,
+        debt_ceiling: u64,
+    ) -> Result<()> {
+        handle_add_psm_asset(ctx, fee_in_bps, fee_out_bps, debt_ceiling)
+    }
+
+    /// Swap an approved stablecoin for PUSD.
+    pub fn psm_swap_in(ctx: Context<PsmSwapIn>, stable_amount: u64) -> Result<()> {
+        handle_psm_swap_in(ctx, stable_amount)
+    }
+
+    /// Swap PUSD for an approved stablecoin.
+    pub fn psm_swap_out(ctx: Context<PsmSwapOut>, pusd_amount: u64) -> Result<()> {
+        handle_psm_swap_out(ctx, pusd_amount)
+    }
+
+    /// Redeem PUSD for collateral at face value from the riskiest positions.
+    pub fn redeem(ctx: Context<Redeem>, pusd_amount: u64, max_fee_bps: u16) -> Result<()> {
+        handle_redeem(ctx, pusd_amount, max_fee_bps)
+    }
+
+    /// Root admin: assign the guardian, risk admin or treasury role.
+    pub fn set_role(ctx: Context<SetRole>, role: ProtocolRole, new_holder: Pubkey) -> Result<()> {
+        handle_set_role(ctx, role, new_holder)
+    }
+
+    /// Root admin: nominate a new root admin (two-step; default cancels).
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        handle_propose_authority(ctx, new_authority)
+    }
+
+    /// Pending admin: accept the nomination and become the root admin.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        handle_accept_authority(ctx)
+    }
+
+    /// Root admin: move an authority-seeded Protocol onto the current layout.
+    pub fn migrate_protocol(ctx: Context<MigrateProtocol>) -> Result<()> {
+        handle_migrate_protocol(ctx)
+    }
+
+    /// Guardian: emergency pause/unpause of PAUSE_* actions protocol-wide.
+    pub fn toggle_pause(ctx: Context<TogglePause>, actions: u8, paused: bool) -> Result<()> {
+        handle_toggle_pause(ctx, actions, paused)
+    }
+
+    /// Guardian: pause/unpause PAUSE_* actions for one collateral type.
+    pub fn toggle_collateral_pause(
+        ctx: Context<ToggleCollateralPause>,
+        actions: u8,
+        paused: bool,
+    ) -> Result<()> {
+        handle_toggle_collateral_pause(ctx, actions, paused)
+    }
+
+    /// Permissionless stability-fee accrual for one collateral type.
+    pub fn accrue(ctx: Context<Accrue>) -> Result<()> {
+        handle_accrue(ctx)
+    }
+
+    /// Create the treasury and its PUSD account.
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        surplus_buffer_pusd: u64,
+    ) -> Result<()> {
+        handle_initialize_treasury(ctx, surplus_buffer_pusd)
+    }
+
+    /// Permissionless: mint accrued fees into the treasury.
+    pub fn collect_fees(ctx: Context<CollectFees>) -> Result<()> {
+        handle_collect_fees(ctx)
+    }
+
+    /// Sweep surplus above the treasury buffer to the configured destination.
+    pub fn sweep_surplus(ctx: Context<SweepSurplus>, amount: u64) -> Result<()> {
+        handle_sweep_surplus(ctx, amount)
+    }
+
+    /// Write off bad debt against accrued surplus and the treasury.
+    pub fn cover_bad_debt(ctx: Context<CoverBadDebt>, max_amount: u64) -> Result<()> {
+        handle_cover_bad_debt(ctx, max_amount)
+    }
+
+    /// Governance: create the sPUSD savings vault.
+    pub fn initialize_savings(ctx: Context<InitializeSavings>, savings_rate_bps: u16) -> Result<()> {
+        handle_initialize_savings(ctx, savings_rate_bps)
+    }
+
+    /// Deposit PUSD for sPUSD.
+    pub fn savings_deposit(ctx: Context<SavingsDeposit>, pusd_amount: u64) -> Result<()> {
+        handle_savings_deposit(ctx, pusd_amount)
+    }
+
+    /// Burn sPUSD for PUSD plus accrued savings yield.
+    pub fn savings_withdraw(ctx: Context<SavingsWithdraw>, shares: u64) -> Result<()> {
+        handle_savings_withdraw(ctx, shares)
+    }
+
+    /// Read-only: sPUSD minted for a PUSD deposit at the current index.
+    pub fn preview_savings_deposit(ctx: Context<PreviewSavings>, pusd_amount: u64) -> Result<u64> {
+        handle_preview_savings_deposit(ctx, pusd_amount)
+    }
+
+    /// Read-only: PUSD paid for withdrawing sPUSD at the current index.
+    pub fn preview_savings_withdraw(ctx: Context<PreviewSavings>, shares: u64) -> Result<u64> {
+        handle_preview_savings_withdraw(ctx, shares)
+    }
+
+    /// Enable the governance timelock for risk parameter changes.
+    pub fn initialize_governance(ctx: Context<InitializeGovernance>, timelock_secs: u64) -> Result<()> {
+        handle_initialize_governance(ctx, timelock_secs)
+    }
+
+    /// Risk admin: queue a typed parameter change behind the timelock.
+    pub fn queue_change(
+        ctx: Context<QueueChange>,
+        proposal_id: u64,
+        action: GovernanceAction,
+    ) -> Result<()> {
+        handle_queue_change(ctx, proposal_id, action)
+    }
+
+    /// Apply a queued change after its timelock has elapsed.
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        handle_execute_change(ctx)
+    }
+
+    /// Risk admin: cancel a queued change.
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        handle_cancel_change(ctx)
+    }
+
+    /// Root admin: trigger global settlement (irreversible).
+    pub fn cage(ctx: Context<Cage>) -> Result<()> {
+        handle_cage(ctx)
+    }
+
+    /// Root admin: freeze a collateral's settlement price at its oracle price.
+    pub fn cage_collateral(ctx: Context<CageCollateral>) -> Result<()> {
+        handle_cage_collateral(ctx)
+    }
+
+    /// Permissionless: settle a position's debt at the frozen price.
+    pub fn settle_position(ctx: Context<SettlePosition>) -> Result<()> {
+        handle_settle_position(ctx)
+    }
+
+    /// Permissionless: settle an auction left running at cage at the frozen price.
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        handle_settle_auction(ctx)
+    }
+
+    /// Owner: withdraw the collateral left in a settled position.
+    pub fn free_collateral(ctx: Context<FreeCollateral>) -> Result<()> {
+        handle_free_collateral(ctx)
+    }
+
+    /// Permissionless: fix the PUSD supply after the settlement delay.
+    pub fn thaw(ctx: Context<Thaw>) -> Result<()> {
+        handle_thaw(ctx)
+    }
+
+    /// Holder: burn PUSD into a settlement claim.
+    pub fn pack_pusd(ctx: Context<PackPusd>, pusd_amount: u64) -> Result<()> {
+        handle_pack_pusd(ctx, pusd_amount)
+    }
+
+    /// Holder: cash a settlement claim for one collateral's pro-rata share.
+    pub fn cash_collateral(ctx: Context<CashCollateral>) -> Result<()> {
+        handle_cash_collateral(ctx)
+    }
+    /// Owner: open a cross-margin account whose debt is booked under one collateral.
+    pub fn open_cross_margin(ctx: Context<OpenCrossMargin>) -> Result<()> {
+        handle_open_cross_margin(ctx)
+    }
+
+    /// Owner: deposit collateral of any registered type into a cross-margin account.
+    pub fn deposit_cross_margin(ctx: Context<DepositCrossMargin>, deposit_amount: u64) -> Result<()> {
+        handle_deposit_cross_margin(ctx, deposit_amount)
+    }
+
+    /// Owner: mint PUSD against all balances of a cross-margin account.
+    pub fn mint_cross_margin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintCrossMargin<'info>>,
+        mint_pusd_6dp: u64,
+    ) -> Result<()> {
+        handle_mint_cross_margin(ctx, mint_pusd_6dp)
+    }
+
+    /// Owner: repay PUSD owed by a cross-margin account.
+    pub fn burn_cross_margin(ctx: Context<BurnCrossMargin>, burn_pusd_6dp: u64) -> Result<()> {
+        handle_burn_cross_margin(ctx, burn_pusd_6dp)
+    }
+
+    /// Owner: withdraw collateral from a cross-margin account.
+    pub fn withdraw_cross_margin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawCrossMargin<'info>>,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        handle_withdraw_cross_margin(ctx, withdraw_amount)
+    }
+
+    /// Permissionless: liquidate an unhealthy cross-margin account.
+    pub fn liquidate_cross_margin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LiquidateCrossMargin<'info>>,
         repay_pusd_6dp: u64,
-        collateral_price_usd_6dp: u128,
-        collateral_decimals: u8,
     ) -> Result<()> {
-        handle_liquidate(
-            ctx,
-            repay_pusd_6dp,
-            collateral_price_usd_6dp,
-            collateral_decimals,
-        )
+        handle_liquidate_cross_margin(ctx, repay_pusd_6dp)
     }
 
-    /// Emergency pause/unpause minting.
-    pub fn toggle_pause(ctx: Context<TogglePause>, paused: bool) -> Result<()> {
-        handle_toggle_pause(ctx, paused)
+    /// Permissionless: settle a cross-margin account at the frozen prices.
+    pub fn settle_cross_margin<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleCrossMargin<'info>>,
+    ) -> Result<()> {
+        handle_settle_cross_margin(ctx)
     }
-}
 
+    /// Owner: withdraw one collateral balance left in a settled cross-margin account.
+    pub fn free_cross_margin_collateral(ctx: Context<FreeCrossMarginCollateral>) -> Result<()> {
+        handle_free_cross_margin_collateral(ctx)
+    }
+}
@@ -0,0 +1,470 @@
+use anchor_lang::prelude::*;
+
+use crate::accounts::CollateralConfig;
+use crate::errors::ErrorCode;
+use crate::state::{
+    CollateralPricingMode, LiquidationPriceMode, OracleKind, OraclePrice, BPS_DENOMINATOR,
+    DEFAULT_MAX_ORACLE_STALENESS_SECS, MAX_ORACLES_PER_COLLATERAL, STAKE_RATE_ONE,
+};
+
+/// ===== Oracle Sources =====
+/// Each supported oracle decodes its own account layout into the generic `OraclePrice`.
+/// Freshness and USD conversion are applied uniformly by the caller.
+pub trait OracleSource {
+    fn load_price(data: &[u8]) -> Result<OraclePrice>;
+}
+
+/// Pyth (v2) push-oracle price accounts.
+pub struct PythOracle;
+
+/// Switchboard On-Demand pull-feed accounts.
+pub struct SwitchboardOnDemandOracle;
+
+/// Decode an oracle account according to the configured kind.
+pub fn load_oracle_price(kind: OracleKind, data: &[u8]) -> Result<OraclePrice> {
+    match kind {
+        OracleKind::Pyth => PythOracle::load_price(data),
+        OracleKind::SwitchboardOnDemand => SwitchboardOnDemandOracle::load_price(data),
+    }
+}
+
+/// Programs that own each oracle kind's accounts.
+pub const PYTH_ORACLE_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// Program that must own oracle accounts of the given kind.
+pub fn oracle_program_id(kind: OracleKind) -> Pubkey {
+    match kind {
+        OracleKind::Pyth => PYTH_ORACLE_PROGRAM_ID,
+        OracleKind::SwitchboardOnDemand => SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+    }
+}
+
+/// Fail unless `oracle` is owned by the program behind `kind`. Only the owner can write an
+/// account, so an oracle is trusted for its owner, not for its key alone.
+pub fn require_oracle_owner(kind: OracleKind, oracle: &AccountInfo) -> Result<()> {
+    require_keys_eq!(*oracle.owner, oracle_program_id(kind), ErrorCode::InvalidOracle);
+    Ok(())
+}
+
+/// ===== Pyth Price Account Layout =====
+/// Pyth (v2) price accounts are fixed-layout, little-endian C structs.
+/// We decode only the fields we need instead of pulling in the Pyth SDK.
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+pub const PYTH_VERSION_2: u32 = 2;
+pub const PYTH_ACCOUNT_TYPE_PRICE: u32 = 3;
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+const PYTH_MAGIC_OFFSET: usize = 0;
+const PYTH_VER_OFFSET: usize = 4;
+const PYTH_ATYPE_OFFSET: usize = 8;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+/// Minimum bytes required to read the header and aggregate price info.
+pub const PYTH_PRICE_MIN_LEN: usize = 240;
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(i32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Option<i64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(i64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_i128(data: &[u8], offset: usize) -> Option<i128> {
+    let bytes = data.get(offset..offset + 16)?;
+    Some(i128::from_le_bytes(bytes.try_into().ok()?))
+}
+
+impl OracleSource for PythOracle {
+    /// Only the aggregate price is used; a non-trading aggregate is treated as stale.
+    fn load_price(data: &[u8]) -> Result<OraclePrice> {
+        require!(data.len() >= PYTH_PRICE_MIN_LEN, ErrorCode::InvalidOracle);
+        require!(
+            read_u32(data, PYTH_MAGIC_OFFSET) == Some(PYTH_MAGIC),
+            ErrorCode::InvalidOracle
+        );
+        require!(
+            read_u32(data, PYTH_VER_OFFSET) == Some(PYTH_VERSION_2),
+            ErrorCode::InvalidOracle
+        );
+        require!(
+            read_u32(data, PYTH_ATYPE_OFFSET) == Some(PYTH_ACCOUNT_TYPE_PRICE),
+            ErrorCode::InvalidOracle
+        );
+        require!(
+            read_u32(data, PYTH_AGG_STATUS_OFFSET) == Some(PYTH_STATUS_TRADING),
+            ErrorCode::OracleStale
+        );
+
+        Ok(OraclePrice {
+            price: read_i64(data, PYTH_AGG_PRICE_OFFSET).ok_or(ErrorCode::InvalidOracle)?,
+            expo: read_i32(data, PYTH_EXPO_OFFSET).ok_or(ErrorCode::InvalidOracle)?,
+            conf: read_u64(data, PYTH_AGG_CONF_OFFSET).ok_or(ErrorCode::InvalidOracle)?,
+            publish_time: read_i64(data, PYTH_TIMESTAMP_OFFSET).ok_or(ErrorCode::InvalidOracle)?,
+        })
+    }
+}
+
+/// ===== Switchboard On-Demand Pull Feed Layout =====
+/// Anchor zero-copy account: 8-byte discriminator followed by `PullFeedAccountData`.
+/// Results are i128 fixed point with 18 decimals.
+pub const SWITCHBOARD_PULL_FEED_DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+pub const SWITCHBOARD_PRECISION: u32 = 18;
+
+/// Exponent used when narrowing Switchboard's 18-decimal values into `OraclePrice`.
+pub const SWITCHBOARD_PRICE_EXPO: i32 = -9;
+
+const SWITCHBOARD_LAST_UPDATE_TS_OFFSET: usize = 2216;
+const SWITCHBOARD_RESULT_VALUE_OFFSET: usize = 2264;
+const SWITCHBOARD_RESULT_STD_DEV_OFFSET: usize = 2280;
+const SWITCHBOARD_RESULT_SLOT_OFFSET: usize = 2368;
+
+/// Minimum bytes required to read the discriminator and current result.
+pub const SWITCHBOARD_PULL_FEED_MIN_LEN: usize = 2384;
+
+impl OracleSource for SwitchboardOnDemandOracle {
+    /// Uses the feed's current (median) result; the std-dev is carried as the confidence.
+    fn load_price(data: &[u8]) -> Result<OraclePrice> {
+        require!(
+            data.len() >= SWITCHBOARD_PULL_FEED_MIN_LEN,
+            ErrorCode::InvalidOracle
+        );
+        require!(
+            data[..8] == SWITCHBOARD_PULL_FEED_DISCRIMINATOR,
+            ErrorCode::InvalidOracle
+        );
+        // A zero result slot means the feed has never been updated
+        require!(
+            read_u64(data, SWITCHBOARD_RESULT_SLOT_OFFSET).ok_or(ErrorCode::InvalidOracle)? > 0,
+            ErrorCode::OracleStale
+        );
+
+        let scale = crate::state::ten_pow_u128(
+            SWITCHBOARD_PRECISION - SWITCHBOARD_PRICE_EXPO.unsigned_abs(),
+        ) as i128;
+        let value = read_i128(data, SWITCHBOARD_RESULT_VALUE_OFFSET).ok_or(ErrorCode::InvalidOracle)?;
+        let std_dev =
+            read_i128(data, SWITCHBOARD_RESULT_STD_DEV_OFFSET).ok_or(ErrorCode::InvalidOracle)?;
+
+        let price: i64 = (value / scale)
+            .try_into()
+            .map_err(|_| ErrorCode::PriceOutOfBounds)?;
+        let conf: u64 = (std_dev.unsigned_abs() / scale as u128)
+            .try_into()
+            .map_err(|_| ErrorCode::PriceOutOfBounds)?;
+
+        Ok(OraclePrice {
+            price,
+            expo: SWITCHBOARD_PRICE_EXPO,
+            conf,
+            publish_time: read_i64(data, SWITCHBOARD_LAST_UPDATE_TS_OFFSET)
+                .ok_or(ErrorCode::InvalidOracle)?,
+        })
+    }
+}
+
+/// ===== SPL Stake Pool Layout =====
+/// Borsh-encoded `StakePool` account of the SPL stake-pool program. Optional fields sit ahead
+/// of the previous-epoch totals, so the account is decoded in full rather than by offset.
+pub const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+
+/// SPL stake-pool program
+pub const SPL_STAKE_POOL_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+#[derive(AnchorDeserialize)]
+struct StakePoolFee {
+    _denominator: u64,
+    _numerator: u64,
+}
+
+#[derive(AnchorDeserialize)]
+enum StakePoolFutureFee {
+    None,
+    One { _fee: StakePoolFee },
+    Two { _fee: StakePoolFee },
+}
+
+#[derive(AnchorDeserialize)]
+struct StakePoolAccount {
+    account_type: u8,
+    _manager: Pubkey,
+    _staker: Pubkey,
+    _stake_deposit_authority: Pubkey,
+    _stake_withdraw_bump_seed: u8,
+    _validator_list: Pubkey,
+    _reserve_stake: Pubkey,
+    pool_mint: Pubkey,
+    _manager_fee_account: Pubkey,
+    _token_program_id: Pubkey,
+    total_lamports: u64,
+    pool_token_supply: u64,
+    _last_update_epoch: u64,
+    _lockup_unix_timestamp: i64,
+    _lockup_epoch: u64,
+    _lockup_custodian: Pubkey,
+    _epoch_fee: StakePoolFee,
+    _next_epoch_fee: StakePoolFutureFee,
+    _preferred_deposit_validator: Option<Pubkey>,
+    _preferred_withdraw_validator: Option<Pubkey>,
+    _stake_deposit_fee: StakePoolFee,
+    _stake_withdrawal_fee: StakePoolFee,
+    _next_stake_withdrawal_fee: StakePoolFutureFee,
+    _stake_referral_fee: u8,
+    _sol_deposit_authority: Option<Pubkey>,
+    _sol_deposit_fee: StakePoolFee,
+    _sol_referral_fee: u8,
+    _sol_withdraw_authority: Option<Pubkey>,
+    _sol_withdrawal_fee: StakePoolFee,
+    _next_sol_withdrawal_fee: StakePoolFutureFee,
+    last_epoch_pool_token_supply: u64,
+    last_epoch_total_lamports: u64,
+}
+
+/// SOL backing of a stake pool's token, now and as of the previous epoch update.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakePoolRate {
+    pub pool_mint: Pubkey,
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+    pub last_epoch_total_lamports: u64,
+    pub last_epoch_pool_token_supply: u64,
+}
+
+/// Decode an SPL stake pool account into its exchange-rate inputs.
+pub fn load_stake_pool_rate(data: &[u8]) -> Result<StakePoolRate> {
+    let pool = StakePoolAccount::deserialize(&mut &data[..]).map_err(|_| ErrorCode::InvalidOracle)?;
+    require!(
+        pool.account_type == STAKE_POOL_ACCOUNT_TYPE,
+        ErrorCode::InvalidOracle
+    );
+    Ok(StakePoolRate {
+        pool_mint: pool.pool_mint,
+        total_lamports: pool.total_lamports,
+        pool_token_supply: pool.pool_token_supply,
+        last_epoch_total_lamports: pool.last_epoch_total_lamports,
+        last_epoch_pool_token_supply: pool.last_epoch_pool_token_supply,
+    })
+}
+
+impl StakePoolRate {
+    /// Lamports per pool token (STAKE_RATE_ONE = 1 SOL per token).
+    pub fn rate(&self) -> Option<u128> {
+        stake_rate(self.total_lamports, self.pool_token_supply)
+    }
+
+    /// The same rate as of the previous epoch update.
+    pub fn last_epoch_rate(&self) -> Option<u128> {
+        stake_rate(self.last_epoch_total_lamports, self.last_epoch_pool_token_supply)
+    }
+
+    /// Returns true if the rate moved by at most `max_change_bps` since the previous epoch.
+    /// A pool without both rates has no history to compare against and fails the check.
+    pub fn within_rate_change(&self, max_change_bps: u16) -> bool {
+        let (Some(rate), Some(last)) = (self.rate(), self.last_epoch_rate()) else {
+            return false;
+        };
+        rate.abs_diff(last).saturating_mul(BPS_DENOMINATOR)
+            <= last.saturating_mul(max_change_bps as u128)
+    }
+
+    /// Convert a SOL-denominated 6dp USD value into the value of one pool token.
+    pub fn scale_6dp(&self, sol_value_6dp: u128) -> Option<u128> {
+        sol_value_6dp
+            .checked_mul(self.rate()?)?
+            .checked_div(STAKE_RATE_ONE)
+    }
+}
+
+fn stake_rate(total_lamports: u64, pool_token_supply: u64) -> Option<u128> {
+    if total_lamports == 0 || pool_token_supply == 0 {
+        return None;
+    }
+    (total_lamports as u128)
+        .checked_mul(STAKE_RATE_ONE)?
+        .checked_div(pool_token_supply as u128)
+}
+
+/// ===== Aggregation =====
+
+/// Median of the given prices (sorted in place). Even counts average the middle pair.
+pub fn median_price_6dp(prices: &mut [u128]) -> Option<u128> {
+    if prices.is_empty() {
+        return None;
+    }
+    prices.sort_unstable();
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        Some(prices[mid])
+    } else {
+        Some(prices[mid - 1].checked_add(prices[mid])? / 2)
+    }
+}
+
+/// Returns true if the spread (max - min) between sources is within `max_deviation_bps`
+/// of the median. A single source always passes.
+pub fn within_max_deviation(prices: &[u128], median_6dp: u128, max_deviation_bps: u16) -> bool {
+    let (Some(min), Some(max)) = (prices.iter().min(), prices.iter().max()) else {
+        return false;
+    };
+    (max - min).saturating_mul(BPS_DENOMINATOR)
+        <= median_6dp.saturating_mul(max_deviation_bps as u128)
+}
+
+/// ===== Confidence-Aware Valuation =====
+
+/// Aggregated collateral price (6dp USD per whole token) with its confidence interval.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CollateralPrice {
+    pub price_6dp: u128,
+    pub conf_6dp: u128,
+}
+
+impl CollateralPrice {
+    /// k * conf, with k expressed in bps.
+    fn band_6dp(&self, conf_multiplier_bps: u16) -> u128 {
+        self.conf_6dp.saturating_mul(conf_multiplier_bps as u128) / BPS_DENOMINATOR
+    }
+
+    /// Conservative price for mint/withdraw LTV checks: price - k*conf.
+    pub fn borrow_price_6dp(&self, conf_multiplier_bps: u16) -> Result<u128> {
+        let price = self.price_6dp.saturating_sub(self.band_6dp(conf_multiplier_bps));
+        require!(price > 0, ErrorCode::PriceOutOfBounds);
+        Ok(price)
+    }
+
+    /// Price for liquidation eligibility: price + k*conf, or the midpoint.
+    pub fn liquidation_price_6dp(&self, conf_multiplier_bps: u16, mode: LiquidationPriceMode) -> u128 {
+        match mode {
+            LiquidationPriceMode::UpperBound => self
+                .price_6dp
+                .saturating_add(self.band_6dp(conf_multiplier_bps)),
+            LiquidationPriceMode::Midpoint => self.price_6dp,
+        }
+    }
+
+    /// Returns true if conf/price is within `max_conf_ratio_bps`.
+    pub fn within_conf_ratio(&self, max_conf_ratio_bps: u16) -> bool {
+        self.conf_6dp.saturating_mul(BPS_DENOMINATOR)
+            <= self.price_6dp.saturating_mul(max_conf_ratio_bps as u128)
+    }
+}
+
+/// Read the registered oracles for a collateral and return the aggregated USD price (6dp) of
+/// one whole token together with its confidence interval.
+/// Oracle accounts are passed in the order registered on `CollateralConfig::oracles`; each key
+/// must match and each account must pass `require_oracle_owner`. Stale or undecodable sources are
+/// skipped; the median of the rest is used once `min_fresh_oracles` agree within
+/// `max_oracle_deviation_bps`, and the median confidence must stay within `max_conf_ratio_bps`.
+/// In StakePool mode the oracles price SOL and the registered stake pool account follows them;
+/// price and confidence are scaled by its SOL-per-token rate, which must not have moved more than
+/// `max_stake_rate_change_bps` since the pool's previous epoch.
+pub fn collateral_price(oracles: &[AccountInfo], cfg: &CollateralConfig) -> Result<CollateralPrice> {
+    collateral_price_at(oracles, cfg, Clock::get()?.unix_timestamp)
+}
+
+/// `collateral_price` with freshness judged against `now_ts`.
+pub fn collateral_price_at(
+    oracles: &[AccountInfo],
+    cfg: &CollateralConfig,
+    now_ts: i64,
+) -> Result<CollateralPrice> {
+    let count = cfg.oracle_count as usize;
+    require!(
+        count > 0
+            && count <= MAX_ORACLES_PER_COLLATERAL
+            && oracles.len() >= cfg.price_account_count(),
+        ErrorCode::InvalidOracle
+    );
+
+    let mut prices = [0u128; MAX_ORACLES_PER_COLLATERAL];
+    let mut confs = [0u128; MAX_ORACLES_PER_COLLATERAL];
+    let mut fresh = 0usize;
+
+    for (feed, oracle) in cfg.oracles[..count].iter().zip(oracles.iter()) {
+        require_keys_eq!(oracle.key(), feed.account, ErrorCode::InvalidOracle);
+        require_oracle_owner(feed.kind, oracle)?;
+
+        let price = {
+            let data = oracle.try_borrow_data()?;
+            match load_oracle_price(feed.kind, &data) {
+                Ok(price) => price,
+                Err(_) => continue,
+            }
+        };
+        if !price.is_fresh(now_ts, DEFAULT_MAX_ORACLE_STALENESS_SECS) {
+            continue;
+        }
+        let (price_6dp, ok) = price.to_usd_6dp();
+        if !ok || price_6dp == 0 {
+            continue;
+        }
+        let Some(conf_6dp) = price.conf_to_usd_6dp() else {
+            continue;
+        };
+
+        prices[fresh] = price_6dp;
+        confs[fresh] = conf_6dp;
+        fresh += 1;
+    }
+
+    require!(
+        fresh > 0 && fresh >= cfg.min_fresh_oracles as usize,
+        ErrorCode::OracleStale
+    );
+
+    let fresh_prices = &mut prices[..fresh];
+    let median_6dp = median_price_6dp(fresh_prices).ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        within_max_deviation(fresh_prices, median_6dp, cfg.max_oracle_deviation_bps),
+        ErrorCode::PriceOutOfBounds
+    );
+
+    let mut price = CollateralPrice {
+        price_6dp: median_6dp,
+        conf_6dp: median_price_6dp(&mut confs[..fresh]).ok_or(ErrorCode::MathOverflow)?,
+    };
+    if cfg.pricing_mode == CollateralPricingMode::StakePool {
+        let pool = &oracles[count];
+        require_keys_eq!(pool.key(), cfg.stake_pool, ErrorCode::InvalidOracle);
+        require_keys_eq!(*pool.owner, SPL_STAKE_POOL_PROGRAM_ID, ErrorCode::InvalidOracle);
+        let rate = {
+            let data = pool.try_borrow_data()?;
+            load_stake_pool_rate(&data)?
+        };
+        require_keys_eq!(rate.pool_mint, cfg.collateral_mint, ErrorCode::MintMismatch);
+        require!(
+            rate.within_rate_change(cfg.max_stake_rate_change_bps),
+            ErrorCode::StakeRateOutOfBounds
+        );
+        price = CollateralPrice {
+            price_6dp: rate.scale_6dp(price.price_6dp).ok_or(ErrorCode::MathOverflow)?,
+            conf_6dp: rate.scale_6dp(price.conf_6dp).ok_or(ErrorCode::MathOverflow)?,
+        };
+        require!(price.price_6dp > 0, ErrorCode::PriceOutOfBounds);
+    }
+    require!(
+        price.within_conf_ratio(cfg.max_conf_ratio_bps),
+        ErrorCode::OracleConfidenceTooWide
+    );
+    Ok(price)
+}
@@ -1,4 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as Token2022Mint;
 
 /// ===== Constants & Fixed-Point Conventions =====
 /// PumpUSD uses 6-decimal fixed point for USD amounts (like USDC).
@@ -8,12 +13,389 @@ pub const PUSD_DECIMALS: u32 = 6;
 /// Basis points (bps): 10_000 = 100.00%
 pub const BPS_DENOMINATOR: u128 = 10_000;
 
-/// Maximum staleness allowed for oracle prices (example; enforce in ix)
+/// Maximum staleness allowed for oracle prices (enforced in `oracle::collateral_price_usd_6dp`)
 pub const DEFAULT_MAX_ORACLE_STALENESS_SECS: i64 = 90;
 
+/// Maximum number of oracle sources aggregated per collateral
+pub const MAX_ORACLES_PER_COLLATERAL: usize = 3;
+
+/// Maximum number of collateral types held by one cross-margin account
+pub const MAX_CROSS_MARGIN_COLLATERALS: usize = 4;
+
+/// Stability fee rate index fixed point: 1e18 = 1.0 (no fees accrued)
+pub const RATE_INDEX_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Seconds per (365-day) year, used to de-annualize stability fees
+pub const SECONDS_PER_YEAR: u128 = 31_536_000;
+
+/// Upper bound for annualized stability fees (bps): 100% APR
+pub const MAX_STABILITY_FEE_BPS: u16 = 10_000;
+
+/// Upper bound for the liquidation penalty charged on liquidated debt (bps): 20%
+pub const MAX_LIQ_PENALTY_BPS: u16 = 2_000;
+
+/// Stability pool accumulator precision (product P and sum S): 1e18 = 1.0
+pub const SP_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// When P drops below SP_PRECISION / SP_SCALE_FACTOR it is rescaled and the scale bumped
+pub const SP_SCALE_FACTOR: u128 = 1_000_000_000;
+
+/// PUSD always left in a stability pool by liquidations (1 PUSD), so P never reaches zero
+pub const MIN_STABILITY_POOL_DEPOSITS: u64 = 1_000_000;
+
+/// Redemption index: half-octave buckets of a position's nominal collateral ratio.
+/// A u64 collateral amount times BPS_DENOMINATOR fits in 78 bits, so 160 buckets cover every ratio.
+pub const REDEMPTION_BUCKETS: usize = 160;
+
+/// Marker for positions without debt (not in the redemption index)
+pub const REDEMPTION_BUCKET_NONE: u8 = u8::MAX;
+
+/// Redemption base rate half-life (12 hours)
+pub const REDEMPTION_BASE_RATE_HALF_LIFE_SECS: i64 = 43_200;
+
+/// Fee source labels emitted in `FeeBooked` (see `pack_label`)
+pub const FEE_SOURCE_STABILITY_FEE: &str = "stability_fee";
+pub const FEE_SOURCE_LIQ_PENALTY: &str = "liq_penalty";
+pub const FEE_SOURCE_REALIZED: &str = "fees_realized";
+pub const FEE_SOURCE_PSM: &str = "psm_fee";
+
+/// Upper bound for a collateral's initial LTV (bps): 95%, always below 100%
+pub const MAX_INITIAL_LTV_BPS: u16 = 9_500;
+
+/// Upper bound for the liquidation bonus (bps): 20%
+pub const MAX_LIQ_BONUS_BPS: u16 = 2_000;
+
+/// Largest change to an LTV threshold in one update (bps)
+pub const MAX_LTV_STEP_BPS: u16 = 1_000;
+
+/// Largest change to the liquidation bonus in one update (bps)
+pub const MAX_LIQ_BONUS_STEP_BPS: u16 = 500;
+
+/// Largest debt ceiling increase in one update (bps of the current ceiling); cuts are unbounded
+pub const MAX_DEBT_CEILING_STEP_BPS: u16 = 5_000;
+
+/// Shortest gap between two step-capped changes to a collateral's risk parameters: 1 day
+pub const MIN_RISK_UPDATE_INTERVAL_SECS: i64 = 86_400;
+
+/// Upper bound for PSM swap fees (bps): 10%
+pub const MAX_PSM_FEE_BPS: u16 = 1_000;
+
+/// Upper bound for the sPUSD savings rate (bps per year): 20%
+pub const MAX_SAVINGS_RATE_BPS: u16 = 2_000;
+
+/// Shortest governance timelock: 1 day
+pub const MIN_TIMELOCK_SECS: u64 = 86_400;
+
+/// Longest governance timelock: 30 days
+pub const MAX_TIMELOCK_SECS: u64 = 30 * 86_400;
+
+/// Queued changes must be executed within this window after their ETA: 14 days
+pub const PROPOSAL_GRACE_PERIOD_SECS: i64 = 14 * 86_400;
+
+/// Pause switches, one bit per action (bitmask on `Protocol` and `CollateralConfig`).
+/// Bit 0 is mint so a legacy `mint_paused = true` byte still reads as "mint paused".
+pub const PAUSE_MINT: u8 = 1 << 0;
+pub const PAUSE_DEPOSIT: u8 = 1 << 1;
+pub const PAUSE_BURN: u8 = 1 << 2;
+/// Also gates redemptions, which take collateral out of positions
+pub const PAUSE_WITHDRAW: u8 = 1 << 3;
+pub const PAUSE_LIQUIDATE: u8 = 1 << 4;
+pub const PAUSE_PSM: u8 = 1 << 5;
+pub const PAUSE_ALL: u8 =
+    PAUSE_MINT | PAUSE_DEPOSIT | PAUSE_BURN | PAUSE_WITHDRAW | PAUSE_LIQUIDATE | PAUSE_PSM;
+/// PSM assets are not collateral types, so the PSM switch is protocol-wide only
+pub const PAUSE_COLLATERAL_ACTIONS: u8 = PAUSE_ALL & !PAUSE_PSM;
+
+/// PUSD token-metadata bounds for the Token-2022 initialization path
+pub const MAX_PUSD_NAME_LEN: usize = 32;
+pub const MAX_PUSD_SYMBOL_LEN: usize = 10;
+pub const MAX_PUSD_URI_LEN: usize = 200;
+
+/// Transfer-hook programs a collateral mint may invoke. Hooks need their extra accounts
+/// threaded through every vault transfer, so none are allowed until that is wired up.
+pub const TRANSFER_HOOK_ALLOWLIST: &[Pubkey] = &[];
+
+/// Stake-pool pricing: LST mints must share SOL's 9 decimals so the pool's lamports-per-token
+/// rate converts a SOL/USD price into a price per whole LST
+pub const SOL_DECIMALS: u8 = 9;
+
+/// Fixed-point scale for stake-pool exchange rates (lamports per pool token)
+pub const STAKE_RATE_ONE: u128 = 1_000_000_000;
+
+/// Minimum time between `cage` and `thaw`, for keepers to settle positions and for PSM exits
+pub const SETTLEMENT_THAW_DELAY_SECS: i64 = 3 * 86_400;
+
 /// Safe ceiling used when converting/scaling to avoid accidental overflow
 pub const U64_MAX_AS_U128: u128 = u64::MAX as u128;
 
+/// ===== Oracle Source Selection =====
+/// Which adapter decodes a collateral's `oracle_account` (see `oracle::OracleSource`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OracleKind {
+    #[default]
+    Pyth,
+    SwitchboardOnDemand,
+}
+
+/// Which price is used to decide liquidation eligibility (and seizure amounts).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LiquidationPriceMode {
+    /// price + k*conf: borrowers get the benefit of the doubt
+    #[default]
+    UpperBound,
+    /// The aggregated (median) price, ignoring the confidence band
+    Midpoint,
+}
+
+/// How the oracle price is turned into a collateral price.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CollateralPricingMode {
+    /// The oracles price the collateral token directly
+    #[default]
+    Direct,
+    /// The oracles price SOL; the value is scaled by a stake pool's SOL-per-token rate
+    StakePool,
+}
+
+/// One registered oracle source: the adapter and the account it reads.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OracleFeed {
+    pub kind: OracleKind,
+    pub account: Pubkey,
+}
+impl OracleFeed {
+    pub const LEN: usize = 1 + 32;
+}
+
+/// One collateral balance held by a cross-margin account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CrossMarginBalance {
+    pub collateral_config: Pubkey,
+    pub amount: u64,
+}
+impl CrossMarginBalance {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Collateral risk parameters to change; `None` leaves a field as is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CollateralParamsUpdate {
+    pub initial_ltv_bps: Option<u16>,
+    pub maintenance_ltv_bps: Option<u16>,
+    pub liq_bonus_bps: Option<u16>,
+    pub debt_ceiling: Option<u64>,
+    pub active: Option<bool>,
+}
+impl CollateralParamsUpdate {
+    pub const LEN: usize = 3 + 3 + 3 + 9 + 2;
+
+    /// Static caps on each provided field (ordering and step sizes need the current config).
+    pub fn is_within_bounds(&self) -> bool {
+        !matches!(self.initial_ltv_bps, Some(ltv) if ltv == 0 || ltv > MAX_INITIAL_LTV_BPS)
+            && !matches!(self.maintenance_ltv_bps, Some(0))
+            && !matches!(self.liq_bonus_bps, Some(bonus) if bonus > MAX_LIQ_BONUS_BPS)
+    }
+}
+
+/// Risk, fee and auction parameters a collateral is listed with (`add_collateral`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollateralRiskParams {
+    pub initial_ltv_bps: u16,
+    pub maintenance_ltv_bps: u16,
+    pub liq_bonus_bps: u16,
+    pub liq_penalty_bps: u16,
+    pub close_factor_bps: u16,
+    pub target_health_bps: u16,
+    pub dust_threshold_pusd: u64,
+    pub debt_ceiling: u64,
+    pub stability_fee_bps: u16,
+    pub redemption_fee_floor_bps: u16,
+    pub auction_start_premium_bps: u16,
+    pub auction_floor_bps: u16,
+    pub auction_duration_secs: u32,
+}
+
+/// Oracle set a collateral is listed with; the feed accounts themselves (and the stake pool in
+/// StakePool mode) are passed as remaining accounts, in the same order as `oracle_kinds`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OracleParams {
+    pub oracle_kinds: Vec<OracleKind>,
+    pub min_fresh_oracles: u8,
+    pub max_oracle_deviation_bps: u16,
+    pub conf_multiplier_bps: u16,
+    pub max_conf_ratio_bps: u16,
+    pub liquidation_price_mode: LiquidationPriceMode,
+    pub pricing_mode: CollateralPricingMode,
+    pub max_stake_rate_change_bps: u16,
+}
+
+/// PSM asset parameters to change; `None` leaves a field as is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PsmParamsUpdate {
+    pub fee_in_bps: Option<u16>,
+    pub fee_out_bps: Option<u16>,
+    pub debt_ceiling: Option<u64>,
+    pub active: Option<bool>,
+}
+impl PsmParamsUpdate {
+    pub const LEN: usize = 3 + 3 + 9 + 2;
+
+    /// Fee caps on each provided field.
+    pub fn is_within_bounds(&self) -> bool {
+        !matches!(self.fee_in_bps, Some(fee) if fee > MAX_PSM_FEE_BPS)
+            && !matches!(self.fee_out_bps, Some(fee) if fee > MAX_PSM_FEE_BPS)
+    }
+}
+
+/// Initial/maintenance LTV and bonus invariants shared by `add_collateral` and updates.
+pub fn collateral_risk_params_valid(
+    initial_ltv_bps: u16,
+    maintenance_ltv_bps: u16,
+    liq_bonus_bps: u16,
+) -> bool {
+    initial_ltv_bps > 0
+        && initial_ltv_bps <= MAX_INITIAL_LTV_BPS
+        && maintenance_ltv_bps > 0
+        && maintenance_ltv_bps <= initial_ltv_bps
+        && liq_bonus_bps <= MAX_LIQ_BONUS_BPS
+}
+
+/// Whether `action` is paused protocol-wide or for this collateral.
+pub fn is_action_paused(protocol_paused: u8, collateral_paused: u8, action: u8) -> bool {
+    (protocol_paused | collateral_paused) & action != 0
+}
+
+/// Set or clear `actions` in a pause mask. Returns the new mask and the bits that flipped.
+pub fn apply_pause(paused_actions: u8, actions: u8, paused: bool) -> (u8, u8) {
+    let updated = if paused {
+        paused_actions | actions
+    } else {
+        paused_actions & !actions
+    };
+    (updated, updated ^ paused_actions)
+}
+
+/// Token-metadata fields accepted by the Token-2022 initialization path. Name and symbol
+/// are required; the URI may be left empty.
+pub fn pusd_metadata_valid(name: &str, symbol: &str, uri: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PUSD_NAME_LEN
+        && !symbol.is_empty()
+        && symbol.len() <= MAX_PUSD_SYMBOL_LEN
+        && uri.len() <= MAX_PUSD_URI_LEN
+}
+
+/// Whether a collateral mint is free of Token-2022 extensions that would let a third party
+/// pull tokens out of the vault or block transfers from it. Transfer fees are allowed: deposits
+/// are credited with what actually reaches the vault. Legacy SPL mints carry no extensions.
+pub fn collateral_mint_extensions_safe(mint_data: &[u8]) -> bool {
+    let mint = match StateWithExtensions::<Token2022Mint>::unpack(mint_data) {
+        Ok(mint) => mint,
+        Err(_) => return false,
+    };
+    let extensions = match mint.get_extension_types() {
+        Ok(extensions) => extensions,
+        Err(_) => return false,
+    };
+    extensions.iter().all(|extension| match extension {
+        ExtensionType::PermanentDelegate | ExtensionType::NonTransferable => false,
+        // An unset hook is only harmless if nobody can set one later
+        ExtensionType::TransferHook => match mint.get_extension::<TransferHook>() {
+            Ok(hook) => {
+                Option::<Pubkey>::from(hook.authority).is_none()
+                    && match Option::<Pubkey>::from(hook.program_id) {
+                        Some(program_id) => TRANSFER_HOOK_ALLOWLIST.contains(&program_id),
+                        None => true,
+                    }
+            }
+            Err(_) => false,
+        },
+        _ => true,
+    })
+}
+
+/// Whether moving a parameter from `old` to `new` stays within `max_step` either way.
+pub fn within_step(old: u64, new: u64, max_step: u64) -> bool {
+    old.abs_diff(new) <= max_step
+}
+
+/// Whether a collateral's step-capped risk parameters may change again, given when they last
+/// did. Without the gap, queued changes could chain several steps in one slot.
+pub fn risk_update_due(last_update_ts: i64, now_ts: i64) -> bool {
+    now_ts.saturating_sub(last_update_ts) >= MIN_RISK_UPDATE_INTERVAL_SECS
+}
+
+/// Outstanding debt after minting `amount` more, or None if it would pass `ceiling`.
+pub fn debt_after_mint(outstanding: u64, amount: u64, ceiling: u64) -> Option<u64> {
+    outstanding.checked_add(amount).filter(|total| *total <= ceiling)
+}
+
+/// Debt ceilings may be cut freely but grow by at most MAX_DEBT_CEILING_STEP_BPS per update
+/// (any amount from zero is allowed, since there is nothing to scale from).
+pub fn debt_ceiling_step_ok(old: u64, new: u64) -> bool {
+    if new <= old || old == 0 {
+        return true;
+    }
+    let max_increase = (old as u128) * (MAX_DEBT_CEILING_STEP_BPS as u128) / BPS_DENOMINATOR;
+    ((new - old) as u128) <= max_increase
+}
+
+/// Delegated protocol roles; the root admin (`Protocol::authority`) assigns them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolRole {
+    Guardian,
+    RiskAdmin,
+    Treasury,
+}
+
+/// Global settlement lifecycle (see `cage`).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SettlementPhase {
+    /// Normal operation
+    #[default]
+    Live,
+    /// Shut down: prices are being frozen and positions settled
+    Caged,
+    /// PUSD supply fixed; holders can pack PUSD and cash it for collateral
+    Thawed,
+}
+
+/// ===== Governance Actions =====
+/// A typed parameter change held by a queued proposal and applied by `execute_change`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernanceAction {
+    SetGlobalDebtCeiling { debt_ceiling: u64 },
+    UpdateCollateralParams { collateral_config: Pubkey, params: CollateralParamsUpdate },
+    SetStabilityFee { collateral_config: Pubkey, stability_fee_bps: u16 },
+    SetSavingsRate { savings_rate_bps: u16 },
+    SetTimelock { timelock_secs: u64 },
+    UpdatePsmParams { psm_config: Pubkey, params: PsmParamsUpdate },
+    SetFinalPrice { collateral_config: Pubkey, final_price_6dp: u64 },
+}
+impl GovernanceAction {
+    /// Tag + largest variant
+    pub const LEN: usize = 1 + 32 + CollateralParamsUpdate::LEN;
+
+    /// Parameter bounds, checked when the change is queued and again when it executes.
+    pub fn is_within_bounds(&self) -> bool {
+        match *self {
+            GovernanceAction::SetGlobalDebtCeiling { .. } => true,
+            GovernanceAction::UpdateCollateralParams { params, .. } => params.is_within_bounds(),
+            GovernanceAction::SetStabilityFee { stability_fee_bps, .. } => {
+                stability_fee_bps <= MAX_STABILITY_FEE_BPS
+            }
+            GovernanceAction::SetSavingsRate { savings_rate_bps } => {
+                savings_rate_bps <= MAX_SAVINGS_RATE_BPS
+            }
+            GovernanceAction::SetTimelock { timelock_secs } => {
+                (MIN_TIMELOCK_SECS..=MAX_TIMELOCK_SECS).contains(&timelock_secs)
+            }
+            GovernanceAction::UpdatePsmParams { params, .. } => params.is_within_bounds(),
+            GovernanceAction::SetFinalPrice { final_price_6dp, .. } => final_price_6dp > 0,
+        }
+    }
+}
+
 /// ===== Oracle Price Model (generic) =====
 /// Lightweight struct compatible with common oracle shapes (e.g., Pyth-like).
 /// Store raw price with an exponent (price = price * 10^expo).
@@ -38,6 +420,18 @@ impl OraclePrice {
         now_ts.saturating_sub(self.publish_time) <= max_age_secs
     }
 
+    /// Convert the confidence interval to a 6-decimal USD fixed-point (u128).
+    /// Uses the same exponent as the price.
+    pub fn conf_to_usd_6dp(&self) -> Option<u128> {
+        let conf = self.conf as u128;
+        let target_exp = self.expo as i128 + PUSD_DECIMALS as i128;
+        if target_exp >= 0 {
+            conf.checked_mul(ten_pow_u128(target_exp as u32))
+        } else {
+            conf.checked_div(ten_pow_u128((-target_exp) as u32))
+        }
+    }
+
     /// Convert the oracle price to a 6-decimal USD fixed-point (u128).
     /// Returns (value in 6dp, ok flag). If conversion fails or price <= 0, ok=false.
     pub fn to_usd_6dp(&self) -> (u128, bool) {
@@ -75,7 +469,7 @@ impl OraclePrice {
 /// ===== Math Helpers =====
 
 /// 10^p for small p (<= 19 fits in i128/u128 comfortably).
-fn ten_pow_u128(p: u32) -> u128 {
+pub fn ten_pow_u128(p: u32) -> u128 {
     const POWS: [u128; 20] = [
         1,
         10,
@@ -131,6 +525,17 @@ pub fn token_amount_to_usd_6dp(amount: u64, token_decimals: u8, price_6dp: u128)
         .checked_div(denom)
 }
 
+/// Re-express a token amount between decimal precisions (rounds down).
+pub fn convert_decimals(amount: u64, from_decimals: u8, to_decimals: u8) -> Option<u64> {
+    let amt = amount as u128;
+    let out = if to_decimals >= from_decimals {
+        amt.checked_mul(ten_pow_u128((to_decimals - from_decimals) as u32))?
+    } else {
+        amt.checked_div(ten_pow_u128((from_decimals - to_decimals) as u32))?
+    };
+    out.try_into().ok()
+}
+
 /// Compute health in basis points: (collateral_value / debt) * 10_000
 /// If debt == 0, returns u128::MAX (infinite health).
 pub fn compute_health_bps(collateral_value_usd_6dp: u128, debt_pusd_6dp: u128) -> u128 {
@@ -174,6 +579,33 @@ pub fn is_above_maintenance(
     health_bps >= maintenance_ltv_bps as u128
 }
 
+/// LTV-weighted collateral value of a cross-margin account, scaled by BPS_DENOMINATOR:
+/// the sum of each balance's value times its LTV (bps).
+fn cross_margin_weighted_value(legs: &[(u128, u16)]) -> u128 {
+    legs.iter().fold(0u128, |sum, (value, ltv_bps)| {
+        sum.saturating_add(value.saturating_mul(*ltv_bps as u128))
+    })
+}
+
+/// Check whether a cross-margin debt stays within the borrowing limit of its collateral.
+/// `legs` holds each balance's (value, initial_ltv_bps); the limit is the LTV-weighted sum,
+/// which for a single collateral matches `check_mint_within_initial_ltv`.
+pub fn cross_margin_within_initial_ltv(legs: &[(u128, u16)], debt_pusd_6dp: u128) -> bool {
+    let weighted = cross_margin_weighted_value(legs);
+    if weighted == 0 {
+        return debt_pusd_6dp == 0;
+    }
+    debt_pusd_6dp.saturating_mul(BPS_DENOMINATOR) <= weighted
+}
+
+/// Check whether a cross-margin account is above maintenance (i.e., not liquidatable).
+/// `legs` holds each balance's (value, maintenance_ltv_bps). Each balance supports debt up to
+/// `value * maintenance_ltv_bps / 10_000`, so lower-LTV collateral counts for less; the account
+/// is healthy while the summed support covers its debt.
+pub fn cross_margin_above_maintenance(legs: &[(u128, u16)], debt_pusd_6dp: u128) -> bool {
+    debt_pusd_6dp.saturating_mul(BPS_DENOMINATOR) <= cross_margin_weighted_value(legs)
+}
+
 /// Apply a liquidation bonus to the seized collateral.
 /// If bonus_bps = 500 (5%), the keeper pays debt * (1 - discount) and receives
 /// collateral accordingly. This helper returns the multiplier (1 + bonus_bps).
@@ -183,6 +615,389 @@ pub fn apply_liquidation_bonus_bps(amount: u128, bonus_bps: u16) -> Option<u128>
     amount.checked_mul(num)?.checked_div(BPS_DENOMINATOR)
 }
 
+/// Smallest repayment that brings a position back to `target_health_bps`, accounting for the
+/// bonus paid out in collateral and the penalty added to debt. Returns None when liquidation
+/// cannot raise health to the target (bonus too large relative to the target), i.e. no cap applies.
+///
+/// Solves C - R*(1 + b) >= T * (D - R*(1 - p)) for R, with b, p, T in bps:
+/// R >= 10_000 * (T*D - 10_000*C) / (T*(10_000 - p) - 10_000*(10_000 + b))
+pub fn repay_to_target_health(
+    collateral_value_usd_6dp: u128,
+    debt_pusd_6dp: u128,
+    target_health_bps: u16,
+    liq_bonus_bps: u16,
+    liq_penalty_bps: u16,
+) -> Option<u128> {
+    let t = target_health_bps as u128;
+    let k_pos = t.checked_mul(BPS_DENOMINATOR.checked_sub(liq_penalty_bps as u128)?)?;
+    let k_neg = BPS_DENOMINATOR.checked_mul(BPS_DENOMINATOR + liq_bonus_bps as u128)?;
+    if k_pos <= k_neg {
+        return None;
+    }
+    let k = k_pos - k_neg;
+
+    let lhs = t.checked_mul(debt_pusd_6dp)?;
+    let rhs = BPS_DENOMINATOR.checked_mul(collateral_value_usd_6dp)?;
+    if lhs <= rhs {
+        return Some(0);
+    }
+    let num = BPS_DENOMINATOR.checked_mul(lhs - rhs)?;
+    num.checked_add(k - 1)?.checked_div(k)
+}
+
+/// Current Dutch auction price: decays linearly from `start_price_6dp` to `floor_price_6dp`
+/// over `duration_secs`, then holds at the floor.
+pub fn dutch_auction_price_6dp(
+    start_price_6dp: u128,
+    floor_price_6dp: u128,
+    start_ts: i64,
+    duration_secs: u32,
+    now_ts: i64,
+) -> u128 {
+    let elapsed = now_ts.saturating_sub(start_ts).max(0) as u128;
+    let duration = duration_secs as u128;
+    if elapsed >= duration || start_price_6dp <= floor_price_6dp {
+        return floor_price_6dp;
+    }
+    // price = start - (start - floor) * elapsed / duration
+    let drop = (start_price_6dp - floor_price_6dp).saturating_mul(elapsed) / duration;
+    start_price_6dp - drop
+}
+
+/// Start and floor prices of an auction curve anchored at `oracle_price_6dp`.
+/// Returns None if either price does not fit in a u64.
+pub fn auction_curve_6dp(
+    oracle_price_6dp: u128,
+    start_premium_bps: u16,
+    floor_bps: u16,
+) -> Option<(u64, u64)> {
+    let start = oracle_price_6dp.checked_mul(start_premium_bps as u128)? / BPS_DENOMINATOR;
+    let floor = oracle_price_6dp.checked_mul(floor_bps as u128)? / BPS_DENOMINATOR;
+    Some((start.try_into().ok()?, floor.try_into().ok()?))
+}
+
+/// Whether an auction's price curve has run its full duration and may be restarted.
+pub fn auction_expired(start_ts: i64, duration_secs: u32, now_ts: i64) -> bool {
+    now_ts.saturating_sub(start_ts) >= duration_secs as i64
+}
+
+/// Redemption index bucket for a position's nominal health, i.e. `compute_health_bps` of raw
+/// collateral units against normalized debt. That ratio is independent of price and of accrued
+/// stability fees, so bucket order matches health order for every position of a collateral type.
+/// Buckets are half-octaves (bucket 2k covers [2^k, 1.5 * 2^k), 2k + 1 covers [1.5 * 2^k, 2^(k+1))).
+pub fn redemption_bucket(nominal_health_bps: u128) -> u8 {
+    if nominal_health_bps == 0 {
+        return 0;
+    }
+    let log2 = 127 - nominal_health_bps.leading_zeros();
+    let half = if log2 > 0 { (nominal_health_bps >> (log2 - 1)) & 1 } else { 0 };
+    let bucket = (log2 as u128) * 2 + half;
+    bucket.min(REDEMPTION_BUCKETS as u128 - 1) as u8
+}
+
+/// Whether a position may be redeemed against next: it must sit in the riskiest non-empty
+/// bucket and be no healthier than the target before it, so targets within a bucket are taken
+/// in ascending nominal health. Pass 0 as `previous_nominal_health_bps` for the first target.
+pub fn redemption_order_ok(
+    bucket: u8,
+    lowest_bucket: u8,
+    nominal_health_bps: u128,
+    previous_nominal_health_bps: u128,
+) -> bool {
+    bucket != REDEMPTION_BUCKET_NONE
+        && bucket == lowest_bucket
+        && nominal_health_bps >= previous_nominal_health_bps
+}
+
+/// Decay the redemption base rate by elapsed time (half-life REDEMPTION_BASE_RATE_HALF_LIFE_SECS).
+/// Whole half-lives halve exactly; the remainder is interpolated linearly.
+pub fn decay_redemption_base_rate(base_rate: u128, elapsed_secs: i64) -> u128 {
+    if elapsed_secs <= 0 || base_rate == 0 {
+        return base_rate;
+    }
+    let halvings = elapsed_secs / REDEMPTION_BASE_RATE_HALF_LIFE_SECS;
+    if halvings >= 128 {
+        return 0;
+    }
+    let rate = base_rate >> halvings;
+    let rem = (elapsed_secs % REDEMPTION_BASE_RATE_HALF_LIFE_SECS) as u128;
+    // rate * (1 - rem / (2 * half_life))
+    rate - rate.saturating_mul(rem) / (2 * REDEMPTION_BASE_RATE_HALF_LIFE_SECS as u128)
+}
+
+/// Base rate after redeeming `redeemed` out of `supply` PUSD: rate += redeemed / supply / 2,
+/// capped at 100%.
+pub fn bump_redemption_base_rate(decayed_base_rate: u128, redeemed: u64, supply: u64) -> u128 {
+    if supply == 0 {
+        return RATE_INDEX_ONE;
+    }
+    let increase = (redeemed as u128).saturating_mul(RATE_INDEX_ONE) / (supply as u128) / 2;
+    decayed_base_rate.saturating_add(increase).min(RATE_INDEX_ONE)
+}
+
+/// Redemption fee (bps): floor plus base rate, capped at 100%.
+pub fn redemption_fee_bps(fee_floor_bps: u16, base_rate: u128) -> u16 {
+    let base_bps = base_rate.saturating_mul(BPS_DENOMINATOR) / RATE_INDEX_ONE;
+    (fee_floor_bps as u128)
+        .saturating_add(base_bps)
+        .min(BPS_DENOMINATOR) as u16
+}
+
+/// Stability pool product-sum accumulators (Liquity-style).
+/// P tracks how much of an initial deposit remains; S tracks collateral gained per unit deposited.
+/// S is kept for the current scale and the one before it; gains older than that are below
+/// SP_PRECISION / SP_SCALE_FACTOR^2 of a deposit and are dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StabilityPoolAccumulators {
+    pub product_p: u128,
+    pub sum_s: u128,
+    pub sum_s_previous: u128,
+    pub scale: u64,
+}
+
+impl StabilityPoolAccumulators {
+    /// Absorb `debt_offset` PUSD of `total_deposits` in exchange for `collateral_gained`.
+    /// Callers keep `debt_offset < total_deposits` (see MIN_STABILITY_POOL_DEPOSITS).
+    pub fn offset(&mut self, total_deposits: u64, debt_offset: u64, collateral_gained: u64) -> Option<()> {
+        if total_deposits == 0 || debt_offset >= total_deposits {
+            return None;
+        }
+        let total = total_deposits as u128;
+
+        // Collateral gained per unit deposited, and the fraction of deposits lost (rounded up)
+        let gain_per_unit = (collateral_gained as u128).checked_mul(SP_PRECISION)?.checked_div(total)?;
+        let loss_per_unit = (debt_offset as u128)
+            .checked_mul(SP_PRECISION)?
+            .checked_add(total - 1)?
+            .checked_div(total)?;
+
+        self.sum_s = self
+            .sum_s
+            .checked_add(gain_per_unit.checked_mul(self.product_p)?.checked_div(SP_PRECISION)?)?;
+
+        let factor = SP_PRECISION.checked_sub(loss_per_unit)?;
+        let mut new_p = self.product_p.checked_mul(factor)?.checked_div(SP_PRECISION)?;
+        if new_p < SP_PRECISION / SP_SCALE_FACTOR {
+            new_p = new_p.checked_mul(SP_SCALE_FACTOR)?;
+            self.sum_s_previous = self.sum_s;
+            self.sum_s = 0;
+            self.scale = self.scale.checked_add(1)?;
+        }
+        if new_p == 0 {
+            return None;
+        }
+        self.product_p = new_p;
+        Some(())
+    }
+
+    /// What remains of `initial_deposit` taken at snapshot (P, scale).
+    pub fn compounded_deposit(&self, initial_deposit: u64, snapshot_p: u128, snapshot_scale: u64) -> Option<u64> {
+        if initial_deposit == 0 || snapshot_p == 0 {
+            return Some(0);
+        }
+        let scaled = (initial_deposit as u128).checked_mul(self.product_p)?.checked_div(snapshot_p)?;
+        let compounded = match self.scale.checked_sub(snapshot_scale)? {
+            0 => scaled,
+            1 => scaled / SP_SCALE_FACTOR,
+            _ => 0,
+        };
+        compounded.try_into().ok()
+    }
+
+    /// Collateral earned by `initial_deposit` since snapshot (P, S, scale).
+    pub fn collateral_gain(
+        &self,
+        initial_deposit: u64,
+        snapshot_p: u128,
+        snapshot_s: u128,
+        snapshot_scale: u64,
+    ) -> Option<u64> {
+        if initial_deposit == 0 || snapshot_p == 0 {
+            return Some(0);
+        }
+        let sum_delta = match self.scale.checked_sub(snapshot_scale)? {
+            0 => self.sum_s.checked_sub(snapshot_s)?,
+            1 => self
+                .sum_s_previous
+                .checked_sub(snapshot_s)?
+                .checked_add(self.sum_s / SP_SCALE_FACTOR)?,
+            _ => 0,
+        };
+        let gain = (initial_deposit as u128).checked_mul(sum_delta)?.checked_div(snapshot_p)?;
+        gain.try_into().ok()
+    }
+}
+
+/// Grow a cumulative rate index by `annual_fee_bps` over `elapsed_secs` (simple interest per step;
+/// compounding comes from accruing on every touch).
+/// index' = index * (1 + fee_bps/10_000 * elapsed/SECONDS_PER_YEAR)
+pub fn accrue_rate_index(rate_index: u128, annual_fee_bps: u16, elapsed_secs: i64) -> Option<u128> {
+    if elapsed_secs <= 0 || annual_fee_bps == 0 {
+        return Some(rate_index);
+    }
+    let growth = rate_index
+        .checked_mul(annual_fee_bps as u128)?
+        .checked_mul(elapsed_secs as u128)?
+        .checked_div(BPS_DENOMINATOR.checked_mul(SECONDS_PER_YEAR)?)?;
+    rate_index.checked_add(growth)
+}
+
+/// Debt owed for a normalized amount at the given rate index (rounded up in the protocol's favor).
+pub fn normalized_to_debt(normalized_debt: u128, rate_index: u128) -> Option<u64> {
+    let num = normalized_debt.checked_mul(rate_index)?;
+    let debt = num.checked_add(RATE_INDEX_ONE - 1)?.checked_div(RATE_INDEX_ONE)?;
+    debt.try_into().ok()
+}
+
+/// Normalized amount for a debt delta. Round up when adding debt and down when removing it,
+/// so rounding never works against the protocol.
+pub fn debt_to_normalized(debt_pusd_6dp: u64, rate_index: u128, round_up: bool) -> Option<u128> {
+    if rate_index == 0 {
+        return None;
+    }
+    let num = (debt_pusd_6dp as u128).checked_mul(RATE_INDEX_ONE)?;
+    if round_up {
+        num.checked_add(rate_index - 1)?.checked_div(rate_index)
+    } else {
+        num.checked_div(rate_index)
+    }
+}
+
+/// Grow the savings index (PUSD per sPUSD, RATE_INDEX_ONE = 1.0) at `savings_rate_bps` over
+/// `elapsed_secs`. Interest is funded from `available_pusd`; if that cannot cover the full rate,
+/// the index only grows by what is funded. Returns (new_index, interest owed to the vault).
+pub fn accrue_savings_index(
+    savings_index: u128,
+    savings_rate_bps: u16,
+    elapsed_secs: i64,
+    total_shares: u64,
+    available_pusd: u64,
+) -> Option<(u128, u64)> {
+    if total_shares == 0 {
+        return Some((savings_index, 0));
+    }
+    let target = accrue_rate_index(savings_index, savings_rate_bps, elapsed_secs)?;
+    // Round interest up so the vault always holds at least shares * index
+    let interest = (total_shares as u128)
+        .checked_mul(target - savings_index)?
+        .checked_add(RATE_INDEX_ONE - 1)?
+        / RATE_INDEX_ONE;
+    if interest <= available_pusd as u128 {
+        return Some((target, interest as u64));
+    }
+    let funded = (available_pusd as u128)
+        .checked_mul(RATE_INDEX_ONE)?
+        .checked_div(total_shares as u128)?;
+    Some((savings_index.checked_add(funded)?, available_pusd))
+}
+
+/// sPUSD minted for a PUSD deposit at the given savings index (rounded down).
+pub fn savings_shares_for_pusd(pusd_amount: u64, savings_index: u128) -> Option<u64> {
+    (pusd_amount as u128)
+        .checked_mul(RATE_INDEX_ONE)?
+        .checked_div(savings_index)?
+        .try_into()
+        .ok()
+}
+
+/// PUSD paid out for redeeming sPUSD at the given savings index (rounded down).
+pub fn savings_pusd_for_shares(shares: u64, savings_index: u128) -> Option<u64> {
+    (shares as u128)
+        .checked_mul(savings_index)?
+        .checked_div(RATE_INDEX_ONE)?
+        .try_into()
+        .ok()
+}
+
+/// Move accrued surplus into realized fees. Returns (PUSD to mint to the treasury, realized fees
+/// after collection).
+pub fn realize_accrued_surplus(accrued_surplus_pusd: u64, realized_fees_pusd: u64) -> Option<(u64, u64)> {
+    Some((accrued_surplus_pusd, realized_fees_pusd.checked_add(accrued_surplus_pusd)?))
+}
+
+/// Treasury PUSD that may be swept: whatever sits above the surplus buffer.
+pub fn sweepable_surplus(treasury_balance: u64, surplus_buffer_pusd: u64) -> u64 {
+    treasury_balance.saturating_sub(surplus_buffer_pusd)
+}
+
+/// Debt that no collateral backs any more: the whole debt once the collateral is exhausted,
+/// zero while any collateral remains.
+pub fn unbacked_debt(collateral_amount: u64, debt_pusd_6dp: u64) -> u64 {
+    if collateral_amount > 0 {
+        0
+    } else {
+        debt_pusd_6dp
+    }
+}
+
+/// Split a bad-debt write-off of up to `max_amount` into (from accrued surplus, burned from the
+/// treasury). Accrued surplus is netted first; the treasury covers the rest as far as it can.
+pub fn bad_debt_cover_split(
+    bad_debt_pusd: u64,
+    max_amount: u64,
+    accrued_surplus_pusd: u64,
+    treasury_balance: u64,
+) -> (u64, u64) {
+    let to_cover = max_amount.min(bad_debt_pusd);
+    let from_accrued = to_cover.min(accrued_surplus_pusd);
+    let from_treasury = (to_cover - from_accrued).min(treasury_balance);
+    (from_accrued, from_treasury)
+}
+
+/// Settle a position at the frozen price: the collateral covering its debt (rounded up, capped
+/// at what the position holds) goes to PUSD holders. Returns (collateral owed, uncovered debt).
+pub fn settlement_collateral_owed(
+    debt_pusd_6dp: u64,
+    collateral_amount: u64,
+    collateral_decimals: u8,
+    final_price_6dp: u64,
+) -> Option<(u64, u64)> {
+    if final_price_6dp == 0 {
+        return None;
+    }
+    let price = final_price_6dp as u128;
+    let needed = (debt_pusd_6dp as u128)
+        .checked_mul(ten_pow_u128(collateral_decimals as u32))?
+        .checked_add(price - 1)?
+        / price;
+    if needed <= collateral_amount as u128 {
+        return Some((needed as u64, 0));
+    }
+
+    let covered = token_amount_to_usd_6dp(collateral_amount, collateral_decimals, price)?;
+    let shortfall = (debt_pusd_6dp as u128).saturating_sub(covered) as u64;
+    Some((collateral_amount, shortfall))
+}
+
+/// Collateral paid for `pusd_amount` of packed PUSD: its pro-rata share of the collateral set
+/// aside for holders, against the supply fixed at `thaw` (rounded down).
+pub fn settlement_cash_amount(
+    pusd_amount: u64,
+    settlement_collateral: u64,
+    pusd_supply: u64,
+) -> Option<u64> {
+    (pusd_amount as u128)
+        .checked_mul(settlement_collateral as u128)?
+        .checked_div(pusd_supply as u128)?
+        .try_into()
+        .ok()
+}
+
+/// PUSD supply that settlement claims are shared across: the mint supply less the treasury's
+/// surplus, which is burned at `thaw`.
+pub fn settlement_pusd_supply(mint_supply: u64, treasury_pusd: u64) -> Option<u64> {
+    mint_supply.checked_sub(treasury_pusd)
+}
+
+/// Pack a short ASCII label into the fixed-width field used by events (truncated to `N` bytes).
+pub fn pack_label<const N: usize>(label: &str) -> [u8; N] {
+    let mut out = [0u8; N];
+    let bytes = label.as_bytes();
+    let n = bytes.len().min(out.len());
+    out[..n].copy_from_slice(&bytes[..n]);
+    out
+}
+
 /// ===== Events =====
 /// Emit these from your instructions for better indexing/analytics UX.
 
@@ -203,9 +1018,28 @@ pub struct CollateralAdded {
     pub protocol: Pubkey,
     pub collateral_mint: Pubkey,
     pub vault: Pubkey,
+    pub oracles: [OracleFeed; MAX_ORACLES_PER_COLLATERAL],
+    pub oracle_count: u8,
+    pub min_fresh_oracles: u8,
+    pub max_oracle_deviation_bps: u16,
+    pub conf_multiplier_bps: u16,
+    pub max_conf_ratio_bps: u16,
+    pub liquidation_price_mode: LiquidationPriceMode,
+    pub stability_fee_bps: u16,
     pub initial_ltv_bps: u16,
     pub maintenance_ltv_bps: u16,
     pub liq_bonus_bps: u16,
+    pub liq_penalty_bps: u16,
+    pub close_factor_bps: u16,
+    pub target_health_bps: u16,
+    pub dust_threshold_pusd: u64,
+    pub auction_start_premium_bps: u16,
+    pub auction_floor_bps: u16,
+    pub auction_duration_secs: u32,
+    pub redemption_fee_floor_bps: u16,
+    pub pricing_mode: CollateralPricingMode,
+    pub stake_pool: Pubkey,
+    pub max_stake_rate_change_bps: u16,
     pub debt_ceiling: u64,
 }
 
@@ -225,6 +1059,14 @@ pub struct Burned {
     pub new_debt_pusd_6dp: u64,
 }
 
+#[event]
+pub struct CollateralWithdrawn {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub withdrawn_amount: u64,
+    pub remaining_collateral_amount: u64,
+}
+
 #[event]
 pub struct Liquidated {
     pub liquidator: Pubkey,
@@ -234,17 +1076,364 @@ pub struct Liquidated {
     pub seized_collateral_amount: u64,
 }
 
+#[event]
+pub struct StabilityFeeAccrued {
+    pub collateral_mint: Pubkey,
+    pub rate_index: u128,
+    pub accrued_fee_pusd_6dp: u64,
+    pub total_debt_pusd_6dp: u64,
+}
+
+#[event]
+pub struct TreasuryInitialized {
+    pub protocol: Pubkey,
+    pub treasury: Pubkey,
+    pub pusd_account: Pubkey,
+    pub surplus_destination: Pubkey,
+    pub surplus_buffer_pusd_6dp: u64,
+}
+
+#[event]
+pub struct FeeBooked {
+    pub protocol: Pubkey,
+    pub source: [u8; 16], // e.g., "stability_fee", "liq_penalty" (pack a short label)
+    pub amount_pusd_6dp: u64,
+    pub accrued_surplus_pusd_6dp: u64,
+    pub realized_fees_pusd_6dp: u64,
+}
+
+#[event]
+pub struct SurplusSwept {
+    pub protocol: Pubkey,
+    pub destination: Pubkey,
+    pub amount_pusd_6dp: u64,
+    pub total_swept_pusd_6dp: u64,
+}
+
+#[event]
+pub struct BadDebtRecognized {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub bad_debt_pusd_6dp: u64,
+    pub total_bad_debt_pusd_6dp: u64,
+}
+
+#[event]
+pub struct BadDebtCovered {
+    pub protocol: Pubkey,
+    pub from_accrued_surplus_pusd_6dp: u64,
+    pub burned_from_treasury_pusd_6dp: u64,
+    pub remaining_bad_debt_pusd_6dp: u64,
+}
+
+#[event]
+pub struct AuctionStarted {
+    pub keeper: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub collateral_amount: u64,
+    pub debt_target_pusd_6dp: u64,
+    pub start_price_6dp: u64,
+    pub floor_price_6dp: u64,
+}
+
+#[event]
+pub struct AuctionTaken {
+    pub keeper: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub collateral_amount: u64,
+    pub paid_pusd_6dp: u64,
+    pub price_6dp: u64,
+    pub debt_remaining_pusd_6dp: u64,
+}
+
+#[event]
+pub struct AuctionRestarted {
+    pub keeper: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub collateral_amount: u64,
+    pub debt_target_pusd_6dp: u64,
+    pub start_price_6dp: u64,
+    pub floor_price_6dp: u64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub returned_collateral_amount: u64,
+    pub shortfall_pusd_6dp: u64,
+}
+
+#[event]
+pub struct StabilityPoolDeposited {
+    pub depositor: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub amount_pusd_6dp: u64,
+    pub deposit_pusd_6dp: u64,
+    pub collateral_gain_paid: u64,
+}
+
+#[event]
+pub struct StabilityPoolWithdrawn {
+    pub depositor: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub amount_pusd_6dp: u64,
+    pub deposit_pusd_6dp: u64,
+    pub collateral_gain_paid: u64,
+}
+
+#[event]
+pub struct StabilityPoolOffset {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub debt_offset_pusd_6dp: u64,
+    pub collateral_gained: u64,
+    pub total_deposits_pusd_6dp: u64,
+}
+
+#[event]
+pub struct PsmAssetAdded {
+    pub protocol: Pubkey,
+    pub stable_mint: Pubkey,
+    pub vault: Pubkey,
+    pub fee_in_bps: u16,
+    pub fee_out_bps: u16,
+    pub debt_ceiling: u64,
+}
+
+#[event]
+pub struct PsmSwappedIn {
+    pub user: Pubkey,
+    pub stable_mint: Pubkey,
+    pub stable_amount: u64,
+    pub pusd_out_6dp: u64,
+    pub fee_pusd_6dp: u64,
+}
+
+#[event]
+pub struct PsmSwappedOut {
+    pub user: Pubkey,
+    pub stable_mint: Pubkey,
+    pub pusd_in_6dp: u64,
+    pub stable_amount: u64,
+    pub fee_pusd_6dp: u64,
+}
+
+#[event]
+pub struct Redeemed {
+    pub redeemer: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub redeemed_pusd_6dp: u64,
+    pub collateral_amount: u64,
+    pub fee_bps: u16,
+    pub positions_touched: u8,
+    pub base_rate: u128,
+}
+
+#[event]
+pub struct SavingsInitialized {
+    pub protocol: Pubkey,
+    pub share_mint: Pubkey,
+    pub pusd_account: Pubkey,
+    pub savings_rate_bps: u16,
+}
+
+#[event]
+pub struct SavingsAccrued {
+    pub protocol: Pubkey,
+    pub savings_index: u128,
+    pub interest_pusd_6dp: u64,
+    pub total_interest_pusd_6dp: u64,
+}
+
+#[event]
+pub struct SavingsDeposited {
+    pub owner: Pubkey,
+    pub pusd_amount_6dp: u64,
+    pub shares: u64,
+    pub savings_index: u128,
+}
+
+#[event]
+pub struct SavingsWithdrawn {
+    pub owner: Pubkey,
+    pub pusd_amount_6dp: u64,
+    pub shares: u64,
+    pub savings_index: u128,
+}
+
+#[event]
+pub struct GovernanceInitialized {
+    pub protocol: Pubkey,
+    pub governance: Pubkey,
+    pub authority: Pubkey,
+    pub timelock_secs: u64,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub governance: Pubkey,
+    pub proposal_id: u64,
+    pub action: GovernanceAction,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub governance: Pubkey,
+    pub proposal_id: u64,
+    pub action: GovernanceAction,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub governance: Pubkey,
+    pub proposal_id: u64,
+    pub action: GovernanceAction,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub protocol: Pubkey,
+    pub role: ProtocolRole,
+    pub old_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub protocol: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub protocol: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct ProtocolMigrated {
+    pub protocol: Pubkey,
+    pub seed_key: Pubkey,
+}
+
 #[event]
 pub struct PauseToggled {
     pub protocol: Pubkey,
+    pub collateral_config: Option<Pubkey>, // None: protocol-wide switches
     pub paused: bool,
+    pub changed_actions: u8, // PAUSE_* bits that actually flipped
+    pub paused_actions: u8,  // resulting mask
 }
 
 #[event]
 pub struct ParameterUpdated {
     pub protocol: Pubkey,
-    pub field: [u8; 16], // e.g., "ltv", "debt_cap" (pack a short label)
+    pub field: [u8; 32], // e.g., "maintenance_ltv_bps", "debt_ceiling" (pack a short label)
     pub old_value: u64,
     pub new_value: u64,
 }
 
+#[event]
+pub struct Caged {
+    pub protocol: Pubkey,
+    pub settlement: Pubkey,
+    pub caged_at: i64,
+}
+
+#[event]
+pub struct CollateralCaged {
+    pub collateral_mint: Pubkey,
+    pub final_price_6dp: u64,
+    pub total_debt_pusd: u64,
+}
+
+#[event]
+pub struct PositionSettled {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub debt_pusd_6dp: u64,
+    pub collateral_amount: u64,  // set aside for PUSD holders
+    pub shortfall_pusd_6dp: u64, // debt the position's collateral could not cover
+}
+
+#[event]
+pub struct Thawed {
+    pub protocol: Pubkey,
+    pub pusd_supply: u64,
+    pub thawed_at: i64,
+}
+
+#[event]
+pub struct PusdPacked {
+    pub owner: Pubkey,
+    pub pusd_amount: u64,
+    pub total_packed: u64,
+}
+
+#[event]
+pub struct SettlementCashed {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub pusd_amount: u64,
+    pub collateral_amount: u64,
+}
+
+#[event]
+pub struct CrossMarginOpened {
+    pub owner: Pubkey,
+    pub account: Pubkey,
+    pub debt_collateral_mint: Pubkey,
+}
+
+#[event]
+pub struct CrossMarginDeposited {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub deposited_amount: u64,
+    pub balance_amount: u64,
+}
+
+#[event]
+pub struct CrossMarginWithdrawn {
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub withdrawn_amount: u64,
+    pub balance_amount: u64,
+}
+
+#[event]
+pub struct CrossMarginMinted {
+    pub owner: Pubkey,
+    pub minted_pusd_6dp: u64,
+    pub new_debt_pusd_6dp: u64,
+}
+
+#[event]
+pub struct CrossMarginBurned {
+    pub owner: Pubkey,
+    pub burned_pusd_6dp: u64,
+    pub new_debt_pusd_6dp: u64,
+}
+
+#[event]
+pub struct CrossMarginLiquidated {
+    pub liquidator: Pubkey,
+    pub owner: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub repaid_pusd_6dp: u64,
+    pub seized_collateral_amount: u64,
+}
+
+#[event]
+pub struct CrossMarginSettled {
+    pub owner: Pubkey,
+    pub debt_pusd_6dp: u64,
+    pub shortfall_pusd_6dp: u64,
+}
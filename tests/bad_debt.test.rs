@@ -0,0 +1,51 @@
+use pusd::state::{bad_debt_cover_split, unbacked_debt};
+
+/// Bad debt tests
+/// Recognition and write-off math, exercised without a validator.
+
+#[test]
+fn test_bad_debt_recognized_only_when_collateral_exhausted() {
+    // A liquidation that seizes everything leaves the remaining debt unbacked
+    assert_eq!(unbacked_debt(0, 35_000_000), 35_000_000);
+
+    // Any collateral left means the position can still be liquidated further
+    assert_eq!(unbacked_debt(1, 35_000_000), 0);
+
+    // Fully repaid positions carry no bad debt
+    assert_eq!(unbacked_debt(0, 0), 0);
+}
+
+#[test]
+fn test_cover_bad_debt_nets_accrued_surplus_first() {
+    // 100 PUSD bad debt, 40 accrued surplus, 500 in the treasury: 40 + 60
+    assert_eq!(
+        bad_debt_cover_split(100_000_000, u64::MAX, 40_000_000, 500_000_000),
+        (40_000_000, 60_000_000)
+    );
+
+    // Accrued surplus alone covers it: the treasury is not touched
+    assert_eq!(
+        bad_debt_cover_split(100_000_000, u64::MAX, 250_000_000, 500_000_000),
+        (100_000_000, 0)
+    );
+
+    // Caller caps the write-off
+    assert_eq!(
+        bad_debt_cover_split(100_000_000, 70_000_000, 40_000_000, 500_000_000),
+        (40_000_000, 30_000_000)
+    );
+}
+
+#[test]
+fn test_cover_bad_debt_leaves_uncovered_remainder() {
+    // Not enough surplus anywhere: what is left stays socialized
+    let (from_accrued, from_treasury) = bad_debt_cover_split(100_000_000, u64::MAX, 10_000_000, 25_000_000);
+    assert_eq!((from_accrued, from_treasury), (10_000_000, 25_000_000));
+    assert_eq!(100_000_000 - from_accrued - from_treasury, 65_000_000);
+
+    // No surplus at all covers nothing
+    assert_eq!(bad_debt_cover_split(100_000_000, u64::MAX, 0, 0), (0, 0));
+
+    // No bad debt: nothing to cover
+    assert_eq!(bad_debt_cover_split(0, u64::MAX, 10_000_000, 25_000_000), (0, 0));
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::non_transferable::NonTransferable;
+use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensionsMut,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use pusd::state::collateral_mint_extensions_safe;
+
+/// Collateral mint extension tests
+/// Mint accounts are packed locally in the Token-2022 layout with a single extension each.
+
+fn base_mint() -> Mint {
+    Mint {
+        mint_authority: COption::Some(Pubkey::new_unique()),
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+}
+
+/// Pack a Token-2022 mint carrying one extension, letting `init` write it.
+fn mint_with_extension(
+    extension: ExtensionType,
+    init: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+) -> Vec<u8> {
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&[extension]).unwrap();
+    let mut data = vec![0u8; len];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    init(&mut state);
+    state.base = base_mint();
+    state.pack_base();
+    state.init_account_type().unwrap();
+    assert_eq!(state.get_extension_types().unwrap(), vec![extension]);
+    data
+}
+
+#[test]
+fn test_plain_and_fee_mints_are_accepted() {
+    let mut legacy = vec![0u8; Mint::LEN];
+    base_mint().pack_into_slice(&mut legacy);
+    assert!(collateral_mint_extensions_safe(&legacy));
+
+    // Transfer fees are absorbed by crediting what reaches the vault
+    let fee = mint_with_extension(ExtensionType::TransferFeeConfig, |state| {
+        state.init_extension::<TransferFeeConfig>(true).unwrap();
+    });
+    assert!(collateral_mint_extensions_safe(&fee));
+
+    // A hook extension with no program and no authority never invokes anything, now or later
+    let unset_hook = mint_with_extension(ExtensionType::TransferHook, |state| {
+        state.init_extension::<TransferHook>(true).unwrap();
+    });
+    assert!(collateral_mint_extensions_safe(&unset_hook));
+
+    // Not a mint at all
+    assert!(!collateral_mint_extensions_safe(&[0u8; 40]));
+}
+
+#[test]
+fn test_unsafe_extensions_are_rejected() {
+    let delegate = mint_with_extension(ExtensionType::PermanentDelegate, |state| {
+        let ext = state.init_extension::<PermanentDelegate>(true).unwrap();
+        ext.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+    });
+    assert!(!collateral_mint_extensions_safe(&delegate));
+
+    let soulbound = mint_with_extension(ExtensionType::NonTransferable, |state| {
+        state.init_extension::<NonTransferable>(true).unwrap();
+    });
+    assert!(!collateral_mint_extensions_safe(&soulbound));
+
+    let hooked = mint_with_extension(ExtensionType::TransferHook, |state| {
+        let ext = state.init_extension::<TransferHook>(true).unwrap();
+        ext.program_id = Some(Pubkey::new_unique()).try_into().unwrap();
+    });
+    assert!(!collateral_mint_extensions_safe(&hooked));
+
+    // No program yet, but its authority could install one after the mint is listed
+    let settable_hook = mint_with_extension(ExtensionType::TransferHook, |state| {
+        let ext = state.init_extension::<TransferHook>(true).unwrap();
+        ext.authority = Some(Pubkey::new_unique()).try_into().unwrap();
+    });
+    assert!(!collateral_mint_extensions_safe(&settable_hook));
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use pusd::accounts::CollateralConfig;
+use pusd::errors::ErrorCode;
+use pusd::instructions::apply_collateral_params;
+use pusd::state::{
+    collateral_risk_params_valid, debt_ceiling_step_ok, risk_update_due, within_step,
+    CollateralParamsUpdate, MAX_INITIAL_LTV_BPS, MAX_LIQ_BONUS_BPS, MAX_LTV_STEP_BPS,
+    MIN_RISK_UPDATE_INTERVAL_SECS,
+};
+
+/// Collateral parameter update bounds
+
+#[test]
+fn test_collateral_risk_param_invariants() {
+    assert!(collateral_risk_params_valid(7_500, 7_000, 500));
+    assert!(collateral_risk_params_valid(MAX_INITIAL_LTV_BPS, 7_000, MAX_LIQ_BONUS_BPS));
+
+    // LTV must stay below 100%, maintenance may not exceed initial, bonus is capped
+    assert!(!collateral_risk_params_valid(10_000, 7_000, 500));
+    assert!(!collateral_risk_params_valid(7_000, 7_500, 500));
+    assert!(!collateral_risk_params_valid(7_500, 7_000, MAX_LIQ_BONUS_BPS + 1));
+    assert!(!collateral_risk_params_valid(0, 0, 0));
+}
+
+#[test]
+fn test_collateral_param_step_sizes() {
+    let step = MAX_LTV_STEP_BPS as u64;
+    assert!(within_step(7_500, 7_500 - step, step));
+    assert!(within_step(7_500, 7_500 + step, step));
+    assert!(!within_step(7_500, 7_500 - step - 1, step));
+
+    // Ceilings can be cut to zero but only grow by half per update
+    assert!(debt_ceiling_step_ok(1_000_000, 0));
+    assert!(debt_ceiling_step_ok(1_000_000, 1_500_000));
+    assert!(!debt_ceiling_step_ok(1_000_000, 1_500_001));
+    assert!(debt_ceiling_step_ok(0, u64::MAX));
+}
+
+fn listed_collateral(listed_at: i64) -> CollateralConfig {
+    let data = vec![0u8; CollateralConfig::LEN];
+    let mut cfg = CollateralConfig::try_deserialize_unchecked(&mut &data[..]).unwrap();
+    cfg.initial_ltv_bps = 7_500;
+    cfg.maintenance_ltv_bps = 7_000;
+    cfg.liq_bonus_bps = 500;
+    cfg.debt_ceiling = 1_000_000;
+    cfg.active = true;
+    cfg.last_risk_update_ts = listed_at;
+    cfg
+}
+
+#[test]
+fn test_risk_update_interval() {
+    let t0 = 1_700_000_000i64;
+    assert!(!risk_update_due(t0, t0));
+    assert!(!risk_update_due(t0, t0 + MIN_RISK_UPDATE_INTERVAL_SECS - 1));
+    assert!(risk_update_due(t0, t0 + MIN_RISK_UPDATE_INTERVAL_SECS));
+}
+
+#[test]
+fn test_queued_steps_cannot_chain_in_one_slot() {
+    let t0 = 1_700_000_000i64;
+    let mut cfg = listed_collateral(t0);
+    let step = CollateralParamsUpdate {
+        maintenance_ltv_bps: Some(6_000),
+        ..Default::default()
+    };
+    let next_step = CollateralParamsUpdate {
+        maintenance_ltv_bps: Some(5_000),
+        ..Default::default()
+    };
+
+    // Too soon after listing
+    assert_eq!(
+        apply_collateral_params(Pubkey::default(), &mut cfg, &step, t0 + 1).unwrap_err(),
+        ErrorCode::RiskUpdateTooSoon.into()
+    );
+
+    let t1 = t0 + MIN_RISK_UPDATE_INTERVAL_SECS;
+    apply_collateral_params(Pubkey::default(), &mut cfg, &step, t1).unwrap();
+    assert_eq!(cfg.maintenance_ltv_bps, 6_000);
+    assert_eq!(cfg.last_risk_update_ts, t1);
+
+    // A second proposal executing in the same slot cannot take another step
+    assert_eq!(
+        apply_collateral_params(Pubkey::default(), &mut cfg, &next_step, t1).unwrap_err(),
+        ErrorCode::RiskUpdateTooSoon.into()
+    );
+    apply_collateral_params(
+        Pubkey::default(),
+        &mut cfg,
+        &next_step,
+        t1 + MIN_RISK_UPDATE_INTERVAL_SECS,
+    )
+    .unwrap();
+    assert_eq!(cfg.maintenance_ltv_bps, 5_000);
+}
+
+#[test]
+fn test_ceiling_cuts_and_deactivation_are_not_spaced() {
+    let t0 = 1_700_000_000i64;
+    let mut cfg = listed_collateral(t0);
+    let wind_down = CollateralParamsUpdate {
+        debt_ceiling: Some(0),
+        active: Some(false),
+        ..Default::default()
+    };
+    apply_collateral_params(Pubkey::default(), &mut cfg, &wind_down, t0).unwrap();
+    assert_eq!(cfg.debt_ceiling, 0);
+    assert!(!cfg.active);
+    assert_eq!(cfg.last_risk_update_ts, t0);
+}
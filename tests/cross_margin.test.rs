@@ -0,0 +1,63 @@
+use pusd::state::{
+    check_mint_within_initial_ltv, cross_margin_above_maintenance,
+    cross_margin_within_initial_ltv,
+};
+
+/// Cross-margin health tests
+
+#[test]
+fn test_single_collateral_matches_isolated_position() {
+    // $10,000 of collateral at 70% initial / 80% maintenance LTV
+    let value = 10_000_000_000u128;
+    for debt in [0u128, 6_999_999_999, 7_000_000_000, 7_000_000_001, 8_000_000_000, 8_000_000_001] {
+        assert_eq!(
+            cross_margin_within_initial_ltv(&[(value, 7_000)], debt),
+            check_mint_within_initial_ltv(value, debt, 0, 7_000)
+        );
+    }
+
+    // Liquidatable once debt exceeds 80% of the collateral value
+    assert!(cross_margin_above_maintenance(&[(value, 8_000)], 8_000_000_000));
+    assert!(!cross_margin_above_maintenance(&[(value, 8_000)], 8_000_000_001));
+
+    // No collateral backs no debt
+    assert!(cross_margin_within_initial_ltv(&[], 0));
+    assert!(!cross_margin_within_initial_ltv(&[], 1));
+    assert!(!cross_margin_within_initial_ltv(&[(value, 0)], 1));
+    assert!(cross_margin_above_maintenance(&[], 0));
+    assert!(!cross_margin_above_maintenance(&[], 1));
+}
+
+#[test]
+fn test_balances_count_at_their_own_ltv() {
+    // $10,000 of SOL at 70% and $5,000 of an LST at 50%: borrow up to $9,500
+    let legs = [(10_000_000_000u128, 7_000u16), (5_000_000_000, 5_000)];
+    assert!(cross_margin_within_initial_ltv(&legs, 9_500_000_000));
+    assert!(!cross_margin_within_initial_ltv(&legs, 9_500_000_001));
+
+    // Either balance alone supports less than the pair
+    assert!(!cross_margin_within_initial_ltv(&legs[..1], 9_500_000_000));
+    assert!(!cross_margin_within_initial_ltv(&legs[1..], 9_500_000_000));
+
+    // At 80% / 62.5% maintenance the pair supports $8,000 + $3,125 of debt
+    let maintenance = [(10_000_000_000u128, 8_000u16), (5_000_000_000, 6_250)];
+    assert!(cross_margin_above_maintenance(&maintenance, 11_125_000_000));
+    assert!(!cross_margin_above_maintenance(&maintenance, 11_125_000_001));
+}
+
+#[test]
+fn test_lower_ltv_collateral_counts_for_less() {
+    // The same $5,000 supports less debt the lower its maintenance LTV
+    let debt = 3_500_000_000u128;
+    assert!(cross_margin_above_maintenance(&[(5_000_000_000, 8_000)], debt));
+    assert!(!cross_margin_above_maintenance(&[(5_000_000_000, 6_000)], debt));
+
+    // Swapping a high-LTV balance for an equally valued low-LTV one makes the account liquidatable
+    let strong = [(10_000_000_000u128, 8_000u16), (5_000_000_000, 8_000)];
+    let weak = [(10_000_000_000u128, 8_000u16), (5_000_000_000, 4_000)];
+    assert!(cross_margin_above_maintenance(&strong, 11_000_000_000));
+    assert!(!cross_margin_above_maintenance(&weak, 11_000_000_000));
+
+    // Maintenance never supports more debt than the collateral is worth
+    assert!(!cross_margin_above_maintenance(&strong, 15_000_000_001));
+}
@@ -0,0 +1,75 @@
+use anchor_lang::AccountDeserialize;
+use pusd::accounts::{CollateralConfig, Protocol};
+use pusd::instructions::reduce_aggregate_debt;
+use pusd::state::debt_after_mint;
+
+/// Debt ceiling tests
+/// Global and per-collateral ceilings checked on mint, and the headroom repayments free up.
+
+fn zeroed<T: AccountDeserialize>(len: usize) -> T {
+    let data = vec![0u8; len];
+    T::try_deserialize_unchecked(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn test_mint_within_ceiling() {
+    assert_eq!(debt_after_mint(0, 1_000_000, 10_000_000), Some(1_000_000));
+    assert_eq!(debt_after_mint(4_000_000, 5_000_000, 10_000_000), Some(9_000_000));
+}
+
+#[test]
+fn test_ceiling_is_inclusive() {
+    assert_eq!(debt_after_mint(9_000_000, 1_000_000, 10_000_000), Some(10_000_000));
+    assert_eq!(debt_after_mint(9_000_000, 1_000_001, 10_000_000), None);
+}
+
+#[test]
+fn test_zero_ceiling_blocks_all_minting() {
+    assert_eq!(debt_after_mint(0, 1, 0), None);
+}
+
+#[test]
+fn test_overflow_counts_as_over_the_ceiling() {
+    assert_eq!(debt_after_mint(u64::MAX, 1, u64::MAX), None);
+}
+
+#[test]
+fn test_repayment_frees_headroom_under_both_ceilings() {
+    let mut protocol: Protocol = zeroed(Protocol::LEN);
+    let mut cfg: CollateralConfig = zeroed(CollateralConfig::LEN);
+    protocol.global_debt_ceiling = 10_000_000;
+    cfg.debt_ceiling = 4_000_000;
+    protocol.total_debt_pusd = 9_000_000;
+    cfg.total_debt_pusd = 4_000_000;
+
+    // The collateral is at its ceiling although the protocol still has headroom
+    assert_eq!(debt_after_mint(cfg.total_debt_pusd, 1, cfg.debt_ceiling), None);
+
+    reduce_aggregate_debt(&mut protocol, &mut cfg, 1_500_000);
+    assert_eq!(protocol.total_debt_pusd, 7_500_000);
+    assert_eq!(cfg.total_debt_pusd, 2_500_000);
+    assert_eq!(
+        debt_after_mint(cfg.total_debt_pusd, 1_500_000, cfg.debt_ceiling),
+        Some(4_000_000)
+    );
+    assert_eq!(
+        debt_after_mint(protocol.total_debt_pusd, 2_500_000, protocol.global_debt_ceiling),
+        Some(10_000_000)
+    );
+    assert_eq!(
+        debt_after_mint(protocol.total_debt_pusd, 2_500_001, protocol.global_debt_ceiling),
+        None
+    );
+}
+
+#[test]
+fn test_reducing_past_zero_saturates() {
+    let mut protocol: Protocol = zeroed(Protocol::LEN);
+    let mut cfg: CollateralConfig = zeroed(CollateralConfig::LEN);
+    protocol.total_debt_pusd = 100;
+    cfg.total_debt_pusd = 50;
+
+    reduce_aggregate_debt(&mut protocol, &mut cfg, 80);
+    assert_eq!(protocol.total_debt_pusd, 20);
+    assert_eq!(cfg.total_debt_pusd, 0);
+}
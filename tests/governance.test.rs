@@ -0,0 +1,123 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AnchorSerialize;
+use pusd::state::{
+    CollateralParamsUpdate, GovernanceAction, PsmParamsUpdate, MAX_INITIAL_LTV_BPS,
+    MAX_LIQ_BONUS_BPS, MAX_PSM_FEE_BPS, MAX_SAVINGS_RATE_BPS, MAX_STABILITY_FEE_BPS,
+    MAX_TIMELOCK_SECS, MIN_TIMELOCK_SECS,
+};
+
+/// Governance timelock tests
+
+#[test]
+fn test_governance_action_bounds() {
+    let collateral_config = Pubkey::new_unique();
+
+    assert!(GovernanceAction::SetGlobalDebtCeiling { debt_ceiling: u64::MAX }.is_within_bounds());
+    assert!(GovernanceAction::UpdateCollateralParams {
+        collateral_config,
+        params: CollateralParamsUpdate {
+            initial_ltv_bps: Some(MAX_INITIAL_LTV_BPS),
+            active: Some(true),
+            ..Default::default()
+        },
+    }
+    .is_within_bounds());
+    assert!(!GovernanceAction::UpdateCollateralParams {
+        collateral_config,
+        params: CollateralParamsUpdate {
+            liq_bonus_bps: Some(MAX_LIQ_BONUS_BPS + 1),
+            ..Default::default()
+        },
+    }
+    .is_within_bounds());
+
+    assert!(GovernanceAction::SetStabilityFee {
+        collateral_config,
+        stability_fee_bps: MAX_STABILITY_FEE_BPS,
+    }
+    .is_within_bounds());
+    assert!(!GovernanceAction::SetStabilityFee {
+        collateral_config,
+        stability_fee_bps: MAX_STABILITY_FEE_BPS + 1,
+    }
+    .is_within_bounds());
+
+    assert!(GovernanceAction::SetSavingsRate { savings_rate_bps: MAX_SAVINGS_RATE_BPS }.is_within_bounds());
+    assert!(!GovernanceAction::SetSavingsRate { savings_rate_bps: MAX_SAVINGS_RATE_BPS + 1 }.is_within_bounds());
+
+    assert!(GovernanceAction::SetTimelock { timelock_secs: MAX_TIMELOCK_SECS }.is_within_bounds());
+    assert!(!GovernanceAction::SetTimelock { timelock_secs: MAX_TIMELOCK_SECS + 1 }.is_within_bounds());
+
+    // The timelock cannot be switched off or shortened below the minimum
+    assert!(GovernanceAction::SetTimelock { timelock_secs: MIN_TIMELOCK_SECS }.is_within_bounds());
+    assert!(!GovernanceAction::SetTimelock { timelock_secs: MIN_TIMELOCK_SECS - 1 }.is_within_bounds());
+    assert!(!GovernanceAction::SetTimelock { timelock_secs: 0 }.is_within_bounds());
+
+    // A settlement price set by governance must be a real price
+    assert!(GovernanceAction::SetFinalPrice { collateral_config, final_price_6dp: 1 }.is_within_bounds());
+    assert!(!GovernanceAction::SetFinalPrice { collateral_config, final_price_6dp: 0 }.is_within_bounds());
+}
+
+#[test]
+fn test_psm_param_changes_are_bounded() {
+    let psm_config = Pubkey::new_unique();
+
+    let activate = GovernanceAction::UpdatePsmParams {
+        psm_config,
+        params: PsmParamsUpdate {
+            active: Some(true),
+            ..Default::default()
+        },
+    };
+    assert!(activate.is_within_bounds());
+
+    assert!(GovernanceAction::UpdatePsmParams {
+        psm_config,
+        params: PsmParamsUpdate {
+            fee_in_bps: Some(MAX_PSM_FEE_BPS),
+            fee_out_bps: Some(MAX_PSM_FEE_BPS),
+            debt_ceiling: Some(u64::MAX),
+            active: Some(false),
+        },
+    }
+    .is_within_bounds());
+    assert!(!PsmParamsUpdate {
+        fee_in_bps: Some(MAX_PSM_FEE_BPS + 1),
+        ..Default::default()
+    }
+    .is_within_bounds());
+    assert!(!PsmParamsUpdate {
+        fee_out_bps: Some(MAX_PSM_FEE_BPS + 1),
+        ..Default::default()
+    }
+    .is_within_bounds());
+}
+
+#[test]
+fn test_every_action_fits_a_proposal() {
+    let target = Pubkey::new_unique();
+    let actions = [
+        GovernanceAction::UpdateCollateralParams {
+            collateral_config: target,
+            params: CollateralParamsUpdate {
+                initial_ltv_bps: Some(1),
+                maintenance_ltv_bps: Some(1),
+                liq_bonus_bps: Some(1),
+                debt_ceiling: Some(1),
+                active: Some(true),
+            },
+        },
+        GovernanceAction::UpdatePsmParams {
+            psm_config: target,
+            params: PsmParamsUpdate {
+                fee_in_bps: Some(1),
+                fee_out_bps: Some(1),
+                debt_ceiling: Some(1),
+                active: Some(true),
+            },
+        },
+    ];
+    for action in actions {
+        assert!(action.try_to_vec().unwrap().len() <= GovernanceAction::LEN);
+    }
+}
@@ -3,6 +3,10 @@ use anchor_lang::prelude::Pubkey;
 use anchor_lang::solana_program::system_program;
 use anchor_lang::InstructionData;
 use anchor_lang::ToAccountMetas;
+use pusd::state::{
+    auction_curve_6dp, auction_expired, compute_health_bps, dutch_auction_price_6dp,
+    repay_to_target_health,
+};
 use solana_program_test::*;
 use solana_sdk::{
     account::Account,
@@ -39,6 +43,66 @@ async fn test_liquidation_flow() {
     assert!(true);
 }
 
+#[test]
+fn test_repay_to_target_health() {
+    // $110 collateral against 100 PUSD, restore to 120% health with a 5% bonus
+    let repay = repay_to_target_health(110_000_000, 100_000_000, 12_000, 500, 0).unwrap();
+    assert_eq!(repay, 66_666_667);
+
+    // Post-liquidation health reaches the target
+    let collateral_after = 110_000_000 - repay * 10_500 / 10_000;
+    let debt_after = 100_000_000 - repay;
+    assert!(compute_health_bps(collateral_after, debt_after) >= 12_000);
+
+    // A penalty added to debt means more must be repaid
+    let with_penalty = repay_to_target_health(110_000_000, 100_000_000, 12_000, 500, 500).unwrap();
+    assert!(with_penalty > repay);
+
+    // Already at target: nothing to repay
+    assert_eq!(repay_to_target_health(120_000_000, 100_000_000, 12_000, 500, 0), Some(0));
+
+    // Bonus outpaces the target: liquidation cannot restore health, so no cap applies
+    assert_eq!(repay_to_target_health(100_000_000, 100_000_000, 10_400, 500, 0), None);
+}
+
+#[test]
+fn test_dutch_auction_price_decay() {
+    // $120 -> $70 over 1,000 seconds
+    let (start, floor, t0) = (120_000_000u128, 70_000_000u128, 1_700_000_000i64);
+    assert_eq!(dutch_auction_price_6dp(start, floor, t0, 1_000, t0), start);
+    assert_eq!(dutch_auction_price_6dp(start, floor, t0, 1_000, t0 + 500), 95_000_000);
+    assert_eq!(dutch_auction_price_6dp(start, floor, t0, 1_000, t0 + 1_000), floor);
+
+    // Holds at the floor once the curve has run out
+    assert_eq!(dutch_auction_price_6dp(start, floor, t0, 1_000, t0 + 50_000), floor);
+
+    // Clock skew before the start never prices above the start
+    assert_eq!(dutch_auction_price_6dp(start, floor, t0, 1_000, t0 - 10), start);
+}
+
+#[test]
+fn test_auction_curve_from_oracle_price() {
+    // $100 oracle, start at 120%, floor at 70%
+    assert_eq!(auction_curve_6dp(100_000_000, 12_000, 7_000), Some((120_000_000, 70_000_000)));
+
+    // A price too large for the u64 fields is refused rather than truncated
+    assert_eq!(auction_curve_6dp(u64::MAX as u128, 12_000, 7_000), None);
+}
+
+#[test]
+fn test_auction_restart_after_full_duration() {
+    let t0 = 1_700_000_000i64;
+    assert!(!auction_expired(t0, 1_000, t0));
+    assert!(!auction_expired(t0, 1_000, t0 + 999));
+    assert!(auction_expired(t0, 1_000, t0 + 1_000));
+
+    // Restarted at a lower oracle price, the curve starts from the new anchor
+    let (start, floor) = auction_curve_6dp(80_000_000, 12_000, 7_000).unwrap();
+    let t1 = t0 + 1_000;
+    assert_eq!(dutch_auction_price_6dp(start as u128, floor as u128, t1, 1_000, t1), 96_000_000);
+    assert!(!auction_expired(t1, 1_000, t1 + 1));
+}
+
 /// Dummy processor so test compiles before real logic is implemented
 pub fn dummy_process_instruction(
     _program_id: &Pubkey,
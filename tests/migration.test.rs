@@ -0,0 +1,123 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator};
+use pusd::accounts::Protocol;
+use pusd::state::{SettlementPhase, PAUSE_MINT};
+
+/// Protocol migration tests
+/// Decoding the original authority-seeded layout and rotating the admin afterwards.
+
+/// Account data as the original program wrote it (89 bytes)
+fn baseline_account(
+    authority: Pubkey,
+    bump: u8,
+    pusd_mint: Pubkey,
+    global_debt_ceiling: u64,
+    mint_paused: bool,
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(Protocol::BASELINE_LEN);
+    data.extend_from_slice(&Protocol::DISCRIMINATOR);
+    data.extend_from_slice(authority.as_ref());
+    data.push(bump);
+    data.extend_from_slice(pusd_mint.as_ref());
+    data.extend_from_slice(&global_debt_ceiling.to_le_bytes());
+    data.push(mint_paused as u8);
+    data.extend_from_slice(&[0; 7]);
+    data
+}
+
+#[test]
+fn test_baseline_len_matches_original_layout() {
+    assert_eq!(Protocol::BASELINE_LEN, 89);
+    let data = baseline_account(Pubkey::new_unique(), 254, Pubkey::new_unique(), 1, false);
+    assert_eq!(data.len(), Protocol::BASELINE_LEN);
+}
+
+#[test]
+fn test_migrate_baseline_account() {
+    let authority = Pubkey::new_unique();
+    let pusd_mint = Pubkey::new_unique();
+    let data = baseline_account(authority, 253, pusd_mint, 5_000_000_000_000, false);
+
+    let migrated = Protocol::from_baseline(&data).unwrap();
+    assert_eq!(migrated.authority, authority);
+    assert_eq!(migrated.bump, 253);
+    assert_eq!(migrated.pusd_mint, pusd_mint);
+    assert_eq!(migrated.global_debt_ceiling, 5_000_000_000_000);
+    assert_eq!(migrated.paused_actions, 0);
+
+    // Fields the baseline lacked are set, not read from whatever followed
+    assert_eq!(migrated.total_debt_pusd, 0);
+    assert_eq!(migrated.accrued_surplus_pusd, 0);
+    assert_eq!(migrated.realized_fees_pusd, 0);
+    assert_eq!(migrated.bad_debt_pusd, 0);
+    assert_eq!(migrated.governance, Pubkey::default());
+    assert_eq!(migrated.guardian, authority);
+    assert_eq!(migrated.risk_admin, authority);
+    assert_eq!(migrated.treasury_authority, authority);
+    assert_eq!(migrated.pending_authority, Pubkey::default());
+    assert_eq!(migrated.seed_key, authority);
+    assert_eq!(migrated.settlement_phase, SettlementPhase::Live);
+}
+
+#[test]
+fn test_migrated_account_round_trips_at_current_len() {
+    let authority = Pubkey::new_unique();
+    let data = baseline_account(authority, 255, Pubkey::new_unique(), 42, true);
+    let migrated = Protocol::from_baseline(&data).unwrap();
+
+    // The handler reallocs to LEN and writes the decoded struct back
+    let mut grown = vec![0u8; Protocol::LEN];
+    migrated.try_serialize(&mut &mut grown[..]).unwrap();
+    let reread = Protocol::try_deserialize(&mut &grown[..]).unwrap();
+    assert_eq!(reread.authority, authority);
+    assert_eq!(reread.global_debt_ceiling, 42);
+    assert_eq!(reread.total_debt_pusd, 0);
+    assert_eq!(reread.seed_key, authority);
+}
+
+#[test]
+fn test_mint_paused_carries_over() {
+    let data = baseline_account(Pubkey::new_unique(), 255, Pubkey::new_unique(), 0, true);
+    let migrated = Protocol::from_baseline(&data).unwrap();
+    assert_eq!(migrated.paused_actions, PAUSE_MINT);
+}
+
+#[test]
+fn test_rejects_non_baseline_data() {
+    let data = baseline_account(Pubkey::new_unique(), 255, Pubkey::new_unique(), 0, false);
+
+    // Already migrated (or any other size)
+    let mut grown = data.clone();
+    grown.resize(Protocol::LEN, 0);
+    assert!(Protocol::from_baseline(&grown).is_none());
+    assert!(Protocol::from_baseline(&data[..Protocol::BASELINE_LEN - 1]).is_none());
+
+    // Some other account type
+    let mut foreign = data;
+    foreign[0] ^= 0xff;
+    assert!(Protocol::from_baseline(&foreign).is_none());
+}
+
+#[test]
+fn test_authority_transfer_after_migration() {
+    let authority = Pubkey::new_unique();
+    let data = baseline_account(authority, 255, Pubkey::new_unique(), 0, false);
+    let mut protocol = Protocol::from_baseline(&data).unwrap();
+
+    // Nothing nominated yet
+    assert_eq!(protocol.accept_pending_authority(), None);
+    assert_eq!(protocol.authority, authority);
+
+    let new_authority = Pubkey::new_unique();
+    protocol.pending_authority = new_authority;
+    assert_eq!(protocol.accept_pending_authority(), Some(authority));
+    assert_eq!(protocol.authority, new_authority);
+    assert_eq!(protocol.pending_authority, Pubkey::default());
+
+    // The PDA seed stays the original authority, and roles are not reassigned
+    assert_eq!(protocol.seed_key, authority);
+    assert_eq!(protocol.guardian, authority);
+
+    // A nomination is consumed by acceptance
+    assert_eq!(protocol.accept_pending_authority(), None);
+}
@@ -0,0 +1,498 @@
+use anchor_lang::prelude::*;
+use pusd::accounts::CollateralConfig;
+use pusd::errors::ErrorCode;
+use pusd::instructions::check_withdrawal_ltv;
+use pusd::oracle::{
+    collateral_price_at, load_oracle_price, load_stake_pool_rate, median_price_6dp, oracle_program_id,
+    require_oracle_owner, CollateralPrice, within_max_deviation, PYTH_ACCOUNT_TYPE_PRICE,
+    PYTH_MAGIC, PYTH_ORACLE_PROGRAM_ID, PYTH_STATUS_TRADING, PYTH_VERSION_2,
+    STAKE_POOL_ACCOUNT_TYPE, SWITCHBOARD_ON_DEMAND_PROGRAM_ID, SWITCHBOARD_PULL_FEED_DISCRIMINATOR,
+};
+use pusd::state::{
+    check_mint_within_initial_ltv, is_above_maintenance, token_amount_to_usd_6dp,
+    CollateralPricingMode, LiquidationPriceMode, OracleFeed, OracleKind,
+};
+use solana_program_test::*;
+use solana_sdk::account::Account;
+
+/// Oracle adapter tests
+/// Price accounts are fabricated locally in each oracle's on-chain layout and loaded
+/// through `solana-program-test`, so no devnet/mainnet feeds are required.
+
+const PYTH_PRICE_ACCOUNT_LEN: usize = 3312;
+const SWITCHBOARD_PULL_FEED_LEN: usize = 3208;
+
+/// Build a Pyth v2 price account with the given aggregate price.
+fn pyth_price_account_data(price: i64, expo: i32, conf: u64, publish_time: i64, status: u32) -> Vec<u8> {
+    let mut data = vec![0u8; PYTH_PRICE_ACCOUNT_LEN];
+    data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+    data[4..8].copy_from_slice(&PYTH_VERSION_2.to_le_bytes());
+    data[8..12].copy_from_slice(&PYTH_ACCOUNT_TYPE_PRICE.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&status.to_le_bytes());
+    data
+}
+
+/// Build a Switchboard On-Demand pull feed with the given current result (18 decimals).
+fn switchboard_pull_feed_data(value: i128, std_dev: i128, last_update_ts: i64, slot: u64) -> Vec<u8> {
+    let mut data = vec![0u8; SWITCHBOARD_PULL_FEED_LEN];
+    data[0..8].copy_from_slice(&SWITCHBOARD_PULL_FEED_DISCRIMINATOR);
+    data[2216..2224].copy_from_slice(&last_update_ts.to_le_bytes());
+    data[2264..2280].copy_from_slice(&value.to_le_bytes());
+    data[2280..2296].copy_from_slice(&std_dev.to_le_bytes());
+    data[2368..2376].copy_from_slice(&slot.to_le_bytes());
+    data
+}
+
+/// Build an SPL stake pool account with no optional fields set.
+fn stake_pool_account_data(
+    pool_mint: Pubkey,
+    total_lamports: u64,
+    pool_token_supply: u64,
+    last_epoch_total_lamports: u64,
+    last_epoch_pool_token_supply: u64,
+) -> Vec<u8> {
+    let fee = [0u8; 16];
+    let mut data = vec![STAKE_POOL_ACCOUNT_TYPE];
+    data.extend_from_slice(&[0u8; 32 * 3 + 1 + 32 * 2]); // manager .. reserve_stake
+    data.extend_from_slice(pool_mint.as_ref());
+    data.extend_from_slice(&[0u8; 32 * 2]); // manager_fee_account, token_program_id
+    data.extend_from_slice(&total_lamports.to_le_bytes());
+    data.extend_from_slice(&pool_token_supply.to_le_bytes());
+    data.extend_from_slice(&[0u8; 8 + 48]); // last_update_epoch, lockup
+    data.extend_from_slice(&fee); // epoch_fee
+    data.extend_from_slice(&[0u8; 3]); // next_epoch_fee, preferred validators
+    data.extend_from_slice(&fee); // stake_deposit_fee
+    data.extend_from_slice(&fee); // stake_withdrawal_fee
+    data.extend_from_slice(&[0u8; 3]); // next_stake_withdrawal_fee, referral fee, deposit authority
+    data.extend_from_slice(&fee); // sol_deposit_fee
+    data.extend_from_slice(&[0u8; 2]); // sol_referral_fee, sol_withdraw_authority
+    data.extend_from_slice(&fee); // sol_withdrawal_fee
+    data.push(0); // next_sol_withdrawal_fee
+    data.extend_from_slice(&last_epoch_pool_token_supply.to_le_bytes());
+    data.extend_from_slice(&last_epoch_total_lamports.to_le_bytes());
+    data
+}
+
+fn oracle_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: Pubkey::new_unique(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Register fabricated accounts, start the bank and read them back.
+async fn load_fabricated_accounts(accounts: Vec<(Pubkey, Vec<u8>)>) -> Vec<Vec<u8>> {
+    let program_id = Pubkey::new_unique();
+    let mut test = ProgramTest::new("pusd", program_id, processor!(dummy_process_instruction));
+    for (key, data) in accounts.iter() {
+        test.add_account(*key, oracle_account(data.clone()));
+    }
+
+    let (mut banks_client, _payer, _recent_blockhash) = test.start().await;
+
+    let mut loaded = Vec::new();
+    for (key, _) in accounts.iter() {
+        let account = banks_client
+            .get_account(*key)
+            .await
+            .unwrap()
+            .expect("fabricated oracle account");
+        loaded.push(account.data);
+    }
+    loaded
+}
+
+#[tokio::test]
+async fn test_pyth_adapter_decodes_price_account() {
+    let feed = Pubkey::new_unique();
+    // $23.45 with a $0.05 confidence band
+    let data = pyth_price_account_data(2_345, -2, 5, 1_700_000_000, PYTH_STATUS_TRADING);
+    let loaded = load_fabricated_accounts(vec![(feed, data)]).await;
+
+    let price = load_oracle_price(OracleKind::Pyth, &loaded[0]).unwrap();
+    assert_eq!(price.price, 2_345);
+    assert_eq!(price.expo, -2);
+    assert_eq!(price.conf, 5);
+    assert_eq!(price.publish_time, 1_700_000_000);
+    assert!(price.is_fresh(1_700_000_060, 90));
+    assert!(!price.is_fresh(1_700_000_091, 90));
+
+    let (usd_6dp, ok) = price.to_usd_6dp();
+    assert!(ok);
+    assert_eq!(usd_6dp, 23_450_000);
+    assert_eq!(price.conf_to_usd_6dp(), Some(50_000));
+}
+
+#[tokio::test]
+async fn test_pyth_adapter_rejects_bad_accounts() {
+    let halted = Pubkey::new_unique();
+    let wrong_magic = Pubkey::new_unique();
+    let truncated = Pubkey::new_unique();
+
+    let halted_data = pyth_price_account_data(2_345, -2, 5, 1_700_000_000, 2);
+    let mut wrong_magic_data = pyth_price_account_data(2_345, -2, 5, 1_700_000_000, PYTH_STATUS_TRADING);
+    wrong_magic_data[0] ^= 0xff;
+    let truncated_data = vec![0u8; 64];
+
+    let loaded = load_fabricated_accounts(vec![
+        (halted, halted_data),
+        (wrong_magic, wrong_magic_data),
+        (truncated, truncated_data),
+    ])
+    .await;
+
+    assert_eq!(
+        load_oracle_price(OracleKind::Pyth, &loaded[0]).unwrap_err(),
+        ErrorCode::OracleStale.into()
+    );
+    assert_eq!(
+        load_oracle_price(OracleKind::Pyth, &loaded[1]).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+    assert_eq!(
+        load_oracle_price(OracleKind::Pyth, &loaded[2]).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+}
+
+#[tokio::test]
+async fn test_switchboard_adapter_decodes_pull_feed() {
+    let feed = Pubkey::new_unique();
+    // $151.123456789 with a $0.25 std-dev, 18-decimal fixed point
+    let value: i128 = 151_123_456_789_000_000_000;
+    let std_dev: i128 = 250_000_000_000_000_000;
+    let data = switchboard_pull_feed_data(value, std_dev, 1_700_000_000, 250_000_000);
+    let loaded = load_fabricated_accounts(vec![(feed, data)]).await;
+
+    let price = load_oracle_price(OracleKind::SwitchboardOnDemand, &loaded[0]).unwrap();
+    assert_eq!(price.price, 151_123_456_789);
+    assert_eq!(price.expo, -9);
+    assert_eq!(price.conf, 250_000_000);
+    assert_eq!(price.publish_time, 1_700_000_000);
+
+    let (usd_6dp, ok) = price.to_usd_6dp();
+    assert!(ok);
+    assert_eq!(usd_6dp, 151_123_456);
+    assert_eq!(price.conf_to_usd_6dp(), Some(250_000));
+}
+
+#[tokio::test]
+async fn test_switchboard_adapter_rejects_bad_accounts() {
+    let never_updated = Pubkey::new_unique();
+    let pyth_layout = Pubkey::new_unique();
+
+    let never_updated_data = switchboard_pull_feed_data(1_000_000_000_000_000_000, 0, 0, 0);
+    let pyth_layout_data = pyth_price_account_data(2_345, -2, 5, 1_700_000_000, PYTH_STATUS_TRADING);
+
+    let loaded = load_fabricated_accounts(vec![
+        (never_updated, never_updated_data),
+        (pyth_layout, pyth_layout_data),
+    ])
+    .await;
+
+    assert_eq!(
+        load_oracle_price(OracleKind::SwitchboardOnDemand, &loaded[0]).unwrap_err(),
+        ErrorCode::OracleStale.into()
+    );
+    // A Pyth account must not decode under the Switchboard adapter
+    assert_eq!(
+        load_oracle_price(OracleKind::SwitchboardOnDemand, &loaded[1]).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+}
+
+#[tokio::test]
+async fn test_stake_pool_rate_prices_lst() {
+    let pool = Pubkey::new_unique();
+    let pool_mint = Pubkey::new_unique();
+    // 1.1 SOL per token now, 1.0995 SOL per token at the previous epoch
+    let data = stake_pool_account_data(pool_mint, 1_100_000, 1_000_000, 1_099_500, 1_000_000);
+    let loaded = load_fabricated_accounts(vec![(pool, data)]).await;
+
+    let rate = load_stake_pool_rate(&loaded[0]).unwrap();
+    assert_eq!(rate.pool_mint, pool_mint);
+    assert_eq!(rate.rate(), Some(1_100_000_000));
+    assert_eq!(rate.last_epoch_rate(), Some(1_099_500_000));
+
+    // $150 SOL prices the LST at $165
+    assert_eq!(rate.scale_6dp(150_000_000), Some(165_000_000));
+
+    // The rate moved ~4.5 bps since the previous epoch
+    assert!(rate.within_rate_change(5));
+    assert!(!rate.within_rate_change(4));
+}
+
+#[tokio::test]
+async fn test_stake_pool_rate_rejects_bad_accounts() {
+    let jumped = Pubkey::new_unique();
+    let empty = Pubkey::new_unique();
+    let not_a_pool = Pubkey::new_unique();
+
+    // Rate doubled in one epoch
+    let jumped_data = stake_pool_account_data(Pubkey::new_unique(), 2_000, 1_000, 1_000, 1_000);
+    let empty_data = stake_pool_account_data(Pubkey::new_unique(), 0, 0, 0, 0);
+    let mut not_a_pool_data = stake_pool_account_data(Pubkey::new_unique(), 1_000, 1_000, 1_000, 1_000);
+    not_a_pool_data[0] = 2;
+
+    let loaded = load_fabricated_accounts(vec![
+        (jumped, jumped_data),
+        (empty, empty_data),
+        (not_a_pool, not_a_pool_data),
+    ])
+    .await;
+
+    assert!(!load_stake_pool_rate(&loaded[0]).unwrap().within_rate_change(1_000));
+    let empty_rate = load_stake_pool_rate(&loaded[1]).unwrap();
+    assert_eq!(empty_rate.rate(), None);
+    assert!(!empty_rate.within_rate_change(10_000));
+    assert_eq!(
+        load_stake_pool_rate(&loaded[2]).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+    assert_eq!(
+        load_stake_pool_rate(&loaded[2][..100]).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+}
+
+#[test]
+fn test_oracle_accounts_must_be_owned_by_their_program() {
+    assert_eq!(oracle_program_id(OracleKind::Pyth), PYTH_ORACLE_PROGRAM_ID);
+    assert_eq!(
+        oracle_program_id(OracleKind::SwitchboardOnDemand),
+        SWITCHBOARD_ON_DEMAND_PROGRAM_ID
+    );
+
+    let key = Pubkey::new_unique();
+    let mut lamports = 1_000_000_000;
+    let mut data = pyth_price_account_data(100_000_000, -6, 50_000, 1_700_000_000, PYTH_STATUS_TRADING);
+    let pyth_owned = AccountInfo::new(
+        &key,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &PYTH_ORACLE_PROGRAM_ID,
+        false,
+        0,
+    );
+    assert!(require_oracle_owner(OracleKind::Pyth, &pyth_owned).is_ok());
+
+    // Right layout, wrong kind: a Pyth account cannot stand in for a Switchboard feed
+    assert_eq!(
+        require_oracle_owner(OracleKind::SwitchboardOnDemand, &pyth_owned).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+
+    // A look-alike account written by anyone else is rejected even though it decodes
+    let forger = Pubkey::new_unique();
+    let mut lamports = 1_000_000_000;
+    let mut data = pyth_price_account_data(100_000_000, -6, 50_000, 1_700_000_000, PYTH_STATUS_TRADING);
+    let forged = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &forger, false, 0);
+    assert!(load_oracle_price(OracleKind::Pyth, &forged.try_borrow_data().unwrap()).is_ok());
+    assert_eq!(
+        require_oracle_owner(OracleKind::Pyth, &forged).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+}
+
+#[test]
+fn test_median_of_fresh_sources() {
+    assert_eq!(median_price_6dp(&mut []), None);
+    assert_eq!(median_price_6dp(&mut [100_000_000]), Some(100_000_000));
+    // Even count averages the middle pair
+    assert_eq!(median_price_6dp(&mut [101_000_000, 99_000_000]), Some(100_000_000));
+    // Odd count picks the middle regardless of input order
+    assert_eq!(
+        median_price_6dp(&mut [150_000_000, 100_000_000, 101_000_000]),
+        Some(101_000_000)
+    );
+}
+
+#[test]
+fn test_max_deviation_guard() {
+    // 2% spread around a $100 median
+    let prices = [99_000_000u128, 100_000_000, 101_000_000];
+    assert!(within_max_deviation(&prices, 100_000_000, 200));
+    assert!(!within_max_deviation(&prices, 100_000_000, 199));
+
+    // A single source has no spread
+    assert!(within_max_deviation(&[42_000_000], 42_000_000, 1));
+}
+
+#[test]
+fn test_confidence_band_valuation() {
+    // $100 with a $2 confidence interval, k = 1.5
+    let price = CollateralPrice {
+        price_6dp: 100_000_000,
+        conf_6dp: 2_000_000,
+    };
+    assert_eq!(price.borrow_price_6dp(15_000).unwrap(), 97_000_000);
+    assert_eq!(
+        price.liquidation_price_6dp(15_000, LiquidationPriceMode::UpperBound),
+        103_000_000
+    );
+    assert_eq!(
+        price.liquidation_price_6dp(15_000, LiquidationPriceMode::Midpoint),
+        100_000_000
+    );
+
+    // conf/price = 2%
+    assert!(price.within_conf_ratio(200));
+    assert!(!price.within_conf_ratio(199));
+
+    // A band as wide as the price leaves nothing to borrow against
+    let wide = CollateralPrice {
+        price_6dp: 1_000_000,
+        conf_6dp: 1_000_000,
+    };
+    assert_eq!(
+        wide.borrow_price_6dp(10_000).unwrap_err(),
+        ErrorCode::PriceOutOfBounds.into()
+    );
+}
+
+const PUBLISH_TIME: i64 = 1_700_000_000;
+const TEN_SOL: u64 = 10_000_000_000;
+
+/// SOL collateral priced by a single Pyth feed: 66% initial / 60% maintenance LTV,
+/// 1.0x confidence band, upper-band liquidation pricing
+fn sol_collateral_config(feed: Pubkey) -> CollateralConfig {
+    let zeroed = vec![0u8; CollateralConfig::LEN];
+    let mut cfg = CollateralConfig::try_deserialize_unchecked(&mut &zeroed[..]).unwrap();
+    cfg.oracles[0] = OracleFeed {
+        kind: OracleKind::Pyth,
+        account: feed,
+    };
+    cfg.oracle_count = 1;
+    cfg.min_fresh_oracles = 1;
+    cfg.max_oracle_deviation_bps = 100;
+    cfg.conf_multiplier_bps = 10_000;
+    cfg.max_conf_ratio_bps = 200;
+    cfg.liquidation_price_mode = LiquidationPriceMode::UpperBound;
+    cfg.pricing_mode = CollateralPricingMode::Direct;
+    cfg.initial_ltv_bps = 6_600;
+    cfg.maintenance_ltv_bps = 6_000;
+    cfg
+}
+
+/// SOL at $100.00 +/- $1.00, in Pyth's own layout
+fn sol_price_data() -> Vec<u8> {
+    pyth_price_account_data(10_000_000_000, -8, 100_000_000, PUBLISH_TIME, PYTH_STATUS_TRADING)
+}
+
+#[test]
+fn test_mint_is_valued_at_the_lower_band() {
+    let feed = Pubkey::new_unique();
+    let cfg = sol_collateral_config(feed);
+    let mut lamports = 1_000_000_000;
+    let mut data = sol_price_data();
+    let oracle = AccountInfo::new(&feed, false, false, &mut lamports, &mut data, &PYTH_ORACLE_PROGRAM_ID, false, 0);
+
+    let price = collateral_price_at(&[oracle], &cfg, PUBLISH_TIME + 30).unwrap();
+    assert_eq!(price.price_6dp, 100_000_000);
+    assert_eq!(price.conf_6dp, 1_000_000);
+
+    // 10 SOL is worth $990 to a borrower, so 66% allows $653.40 of PUSD
+    let borrow_price = price.borrow_price_6dp(cfg.conf_multiplier_bps).unwrap();
+    let value = token_amount_to_usd_6dp(TEN_SOL, 9, borrow_price).unwrap();
+    assert_eq!(value, 990_000_000);
+    assert!(check_mint_within_initial_ltv(value, 0, 653_400_000, cfg.initial_ltv_bps));
+    assert!(!check_mint_within_initial_ltv(value, 0, 653_400_001, cfg.initial_ltv_bps));
+}
+
+#[test]
+fn test_withdraw_is_valued_at_the_lower_band() {
+    let feed = Pubkey::new_unique();
+    let cfg = sol_collateral_config(feed);
+    let mut lamports = 1_000_000_000;
+    let mut data = sol_price_data();
+    let oracle = AccountInfo::new(&feed, false, false, &mut lamports, &mut data, &PYTH_ORACLE_PROGRAM_ID, false, 0);
+
+    let borrow_price = collateral_price_at(&[oracle], &cfg, PUBLISH_TIME + 30)
+        .unwrap()
+        .borrow_price_6dp(cfg.conf_multiplier_bps)
+        .unwrap();
+
+    // $600 of debt needs $909.09 of collateral at 66%: 9.2 SOL ($910.80) is enough, 9.1 is not
+    let check = |remaining| {
+        check_withdrawal_ltv(
+            TEN_SOL,
+            remaining,
+            600_000_000,
+            9,
+            borrow_price,
+            cfg.initial_ltv_bps,
+            cfg.maintenance_ltv_bps,
+        )
+    };
+    assert!(check(9_200_000_000).is_ok());
+    assert_eq!(check(9_100_000_000).unwrap_err(), ErrorCode::LtvExceeded.into());
+}
+
+#[test]
+fn test_liquidation_is_valued_at_the_configured_band() {
+    let feed = Pubkey::new_unique();
+    let mut cfg = sol_collateral_config(feed);
+    let mut lamports = 1_000_000_000;
+    let mut data = sol_price_data();
+    let oracle = AccountInfo::new(&feed, false, false, &mut lamports, &mut data, &PYTH_ORACLE_PROGRAM_ID, false, 0);
+    let price = collateral_price_at(&[oracle], &cfg, PUBLISH_TIME + 30).unwrap();
+
+    // $1,670 of debt: below maintenance at the $100 midpoint, above it at the $101 upper band
+    let debt = 1_670_000_000u128;
+    let liquidatable = |cfg: &CollateralConfig| {
+        let liq_price = price.liquidation_price_6dp(cfg.conf_multiplier_bps, cfg.liquidation_price_mode);
+        let value = token_amount_to_usd_6dp(TEN_SOL, 9, liq_price).unwrap();
+        !is_above_maintenance(value, debt, cfg.maintenance_ltv_bps)
+    };
+    assert!(!liquidatable(&cfg));
+    cfg.liquidation_price_mode = LiquidationPriceMode::Midpoint;
+    assert!(liquidatable(&cfg));
+}
+
+#[test]
+fn test_stale_or_unregistered_feeds_cannot_price() {
+    let feed = Pubkey::new_unique();
+    let cfg = sol_collateral_config(feed);
+
+    let mut lamports = 1_000_000_000;
+    let mut data = sol_price_data();
+    let oracle = AccountInfo::new(&feed, false, false, &mut lamports, &mut data, &PYTH_ORACLE_PROGRAM_ID, false, 0);
+    assert_eq!(
+        collateral_price_at(&[oracle], &cfg, PUBLISH_TIME + 91).unwrap_err(),
+        ErrorCode::OracleStale.into()
+    );
+
+    // A valid Pyth account that is not the one registered on the collateral
+    let other = Pubkey::new_unique();
+    let mut lamports = 1_000_000_000;
+    let mut data = sol_price_data();
+    let oracle = AccountInfo::new(&other, false, false, &mut lamports, &mut data, &PYTH_ORACLE_PROGRAM_ID, false, 0);
+    assert_eq!(
+        collateral_price_at(&[oracle], &cfg, PUBLISH_TIME + 30).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+
+    // No price account at all
+    assert_eq!(
+        collateral_price_at(&[], &cfg, PUBLISH_TIME + 30).unwrap_err(),
+        ErrorCode::InvalidOracle.into()
+    );
+}
+
+/// Dummy processor; these tests only exercise account decoding and aggregation
+pub fn dummy_process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use pusd::state::{
+    apply_pause, is_action_paused, PAUSE_ALL, PAUSE_BURN, PAUSE_COLLATERAL_ACTIONS, PAUSE_DEPOSIT,
+    PAUSE_LIQUIDATE, PAUSE_MINT, PAUSE_PSM, PAUSE_WITHDRAW,
+};
+
+/// Pause switch tests
+
+#[test]
+fn test_protocol_and_collateral_masks_combine() {
+    // A legacy `mint_paused = true` byte reads as mint paused only
+    assert!(is_action_paused(1, 0, PAUSE_MINT));
+    assert!(!is_action_paused(1, 0, PAUSE_BURN));
+
+    // Either scope pausing an action is enough
+    assert!(is_action_paused(0, PAUSE_WITHDRAW, PAUSE_WITHDRAW));
+    assert!(is_action_paused(PAUSE_LIQUIDATE, 0, PAUSE_LIQUIDATE));
+    assert!(!is_action_paused(PAUSE_DEPOSIT, PAUSE_MINT, PAUSE_LIQUIDATE));
+
+    assert_eq!(PAUSE_COLLATERAL_ACTIONS & PAUSE_PSM, 0);
+    assert_eq!(PAUSE_COLLATERAL_ACTIONS | PAUSE_PSM, PAUSE_ALL);
+}
+
+#[test]
+fn test_apply_pause_reports_changed_bits() {
+    let (mask, changed) = apply_pause(0, PAUSE_MINT | PAUSE_DEPOSIT, true);
+    assert_eq!(mask, PAUSE_MINT | PAUSE_DEPOSIT);
+    assert_eq!(changed, PAUSE_MINT | PAUSE_DEPOSIT);
+
+    // Re-pausing an already paused action flips nothing for it
+    let (mask, changed) = apply_pause(mask, PAUSE_MINT | PAUSE_BURN, true);
+    assert_eq!(mask, PAUSE_MINT | PAUSE_DEPOSIT | PAUSE_BURN);
+    assert_eq!(changed, PAUSE_BURN);
+
+    let (mask, changed) = apply_pause(mask, PAUSE_DEPOSIT | PAUSE_PSM, false);
+    assert_eq!(mask, PAUSE_MINT | PAUSE_BURN);
+    assert_eq!(changed, PAUSE_DEPOSIT);
+}
@@ -0,0 +1,21 @@
+use pusd::state::{convert_decimals, PUSD_DECIMALS};
+
+/// Peg Stability Module conversion tests
+
+const PUSD: u8 = PUSD_DECIMALS as u8;
+
+#[test]
+fn test_stablecoin_amounts_convert_one_to_one() {
+    // USDC (6dp) maps 1:1 onto PUSD units
+    assert_eq!(convert_decimals(1_250_000, 6, PUSD), Some(1_250_000));
+
+    // 8dp and 9dp stables scale down, rounding toward the protocol
+    assert_eq!(convert_decimals(1_250_000_00, 8, PUSD), Some(1_250_000));
+    assert_eq!(convert_decimals(1_999, 9, PUSD), Some(1));
+
+    // 2dp stables scale up
+    assert_eq!(convert_decimals(125, 2, PUSD), Some(1_250_000));
+
+    // PUSD -> 18dp overflows u64 for large amounts instead of wrapping
+    assert_eq!(convert_decimals(u64::MAX, PUSD, 18), None);
+}
@@ -0,0 +1,122 @@
+use anchor_lang::AccountDeserialize;
+use pusd::accounts::{CollateralConfig, Position};
+use pusd::instructions::{lowest_redemption_bucket, reindex_position};
+use pusd::state::{
+    bump_redemption_base_rate, compute_health_bps, decay_redemption_base_rate, redemption_bucket,
+    redemption_fee_bps, redemption_order_ok, RATE_INDEX_ONE, REDEMPTION_BASE_RATE_HALF_LIFE_SECS,
+    REDEMPTION_BUCKETS, REDEMPTION_BUCKET_NONE,
+};
+
+/// Redemption index and fee tests
+
+fn zeroed<T: AccountDeserialize>(len: usize) -> T {
+    let data = vec![0u8; len];
+    T::try_deserialize_unchecked(&mut &data[..]).unwrap()
+}
+
+fn indexed_position(cfg: &mut CollateralConfig, collateral_amount: u64) -> Position {
+    let mut pos: Position = zeroed(Position::LEN);
+    pos.collateral_amount = collateral_amount;
+    pos.normalized_debt = 1_000_000_000;
+    pos.redemption_bucket = REDEMPTION_BUCKET_NONE;
+    reindex_position(cfg, &mut pos);
+    pos
+}
+
+fn nominal_health(pos: &Position) -> u128 {
+    compute_health_bps(pos.collateral_amount as u128, pos.normalized_debt)
+}
+
+#[test]
+fn test_redemption_buckets_follow_health_order() {
+    // Half-octave buckets: [2^k, 1.5 * 2^k) and [1.5 * 2^k, 2^(k+1))
+    assert_eq!(redemption_bucket(0), 0);
+    assert_eq!(redemption_bucket(1), 0);
+    assert_eq!(redemption_bucket(2), 2);
+    assert_eq!(redemption_bucket(3), 3);
+    assert_eq!(redemption_bucket(4), 4);
+    assert_eq!(redemption_bucket(5), 4);
+    assert_eq!(redemption_bucket(6), 5);
+    assert_eq!(redemption_bucket(7), 5);
+    assert_eq!(redemption_bucket(8), 6);
+
+    // Riskier positions (less collateral per unit of debt) never land in a later bucket
+    let risky = compute_health_bps(1_100_000_000, 1_000_000_000);
+    let safe = compute_health_bps(2_500_000_000, 1_000_000_000);
+    assert!(redemption_bucket(risky) < redemption_bucket(safe));
+
+    // The largest possible ratio still fits the index
+    let max = compute_health_bps(u64::MAX as u128, 1);
+    assert!((redemption_bucket(max) as usize) < REDEMPTION_BUCKETS);
+}
+
+#[test]
+fn test_redemption_base_rate_decay_and_fee() {
+    let half_life = REDEMPTION_BASE_RATE_HALF_LIFE_SECS;
+
+    // Decay halves once per half-life and interpolates in between
+    assert_eq!(decay_redemption_base_rate(RATE_INDEX_ONE, 0), RATE_INDEX_ONE);
+    assert_eq!(decay_redemption_base_rate(RATE_INDEX_ONE, half_life), RATE_INDEX_ONE / 2);
+    assert_eq!(
+        decay_redemption_base_rate(RATE_INDEX_ONE, half_life / 2),
+        RATE_INDEX_ONE * 3 / 4
+    );
+    assert_eq!(decay_redemption_base_rate(RATE_INDEX_ONE, 2 * half_life), RATE_INDEX_ONE / 4);
+    assert_eq!(decay_redemption_base_rate(RATE_INDEX_ONE, 200 * half_life), 0);
+
+    // Redeeming 1% of supply adds 0.5% to the base rate, capped at 100%
+    assert_eq!(
+        bump_redemption_base_rate(0, 1_000_000, 100_000_000),
+        RATE_INDEX_ONE / 200
+    );
+    assert_eq!(bump_redemption_base_rate(RATE_INDEX_ONE, 1, 100), RATE_INDEX_ONE);
+    assert_eq!(bump_redemption_base_rate(0, 1, 0), RATE_INDEX_ONE);
+
+    // Fee = floor + base rate
+    assert_eq!(redemption_fee_bps(50, 0), 50);
+    assert_eq!(redemption_fee_bps(50, RATE_INDEX_ONE / 100), 150);
+    assert_eq!(redemption_fee_bps(50, RATE_INDEX_ONE), 10_000);
+}
+
+#[test]
+fn test_redemption_targets_go_riskiest_first() {
+    let mut cfg: CollateralConfig = zeroed(CollateralConfig::LEN);
+    // 1.25x and 1.4x share a bucket; 2.5x sits two buckets later
+    let riskiest = indexed_position(&mut cfg, 1_250_000_000);
+    let middle = indexed_position(&mut cfg, 1_400_000_000);
+    let safe = indexed_position(&mut cfg, 2_500_000_000);
+    let lowest = lowest_redemption_bucket(&cfg);
+    assert_eq!(riskiest.redemption_bucket, lowest);
+    assert_eq!(middle.redemption_bucket, lowest);
+    assert!(safe.redemption_bucket > lowest);
+
+    // Within the bucket, targets go in ascending nominal health
+    assert!(redemption_order_ok(riskiest.redemption_bucket, lowest, nominal_health(&riskiest), 0));
+    assert!(redemption_order_ok(
+        middle.redemption_bucket,
+        lowest,
+        nominal_health(&middle),
+        nominal_health(&riskiest)
+    ));
+    assert!(!redemption_order_ok(
+        riskiest.redemption_bucket,
+        lowest,
+        nominal_health(&riskiest),
+        nominal_health(&middle)
+    ));
+
+    // Healthier buckets wait until the riskier ones are empty
+    assert!(!redemption_order_ok(safe.redemption_bucket, lowest, nominal_health(&safe), 0));
+
+    // Debt-free positions are never targets
+    let mut empty: CollateralConfig = zeroed(CollateralConfig::LEN);
+    let mut debt_free: Position = zeroed(Position::LEN);
+    debt_free.redemption_bucket = REDEMPTION_BUCKET_NONE;
+    reindex_position(&mut empty, &mut debt_free);
+    assert!(!redemption_order_ok(
+        debt_free.redemption_bucket,
+        lowest_redemption_bucket(&empty),
+        0,
+        0
+    ));
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::Pubkey;
+use pusd::accounts::Protocol;
+use pusd::instructions::init_protocol_state;
+use pusd::state::{ProtocolRole, SettlementPhase};
+
+/// Protocol role tests
+/// Role assignment via `set_role` and the separation the role-gated handlers rely on.
+
+const ROLES: [ProtocolRole; 3] = [
+    ProtocolRole::Guardian,
+    ProtocolRole::RiskAdmin,
+    ProtocolRole::Treasury,
+];
+
+/// Role each gated handler checks (`has_one` on its Protocol account)
+const GATED_HANDLERS: [(&str, ProtocolRole); 8] = [
+    ("toggle_pause", ProtocolRole::Guardian),
+    ("toggle_collateral_pause", ProtocolRole::Guardian),
+    ("add_collateral", ProtocolRole::RiskAdmin),
+    ("add_psm_asset", ProtocolRole::RiskAdmin),
+    ("queue_change", ProtocolRole::RiskAdmin),
+    ("cancel_change", ProtocolRole::RiskAdmin),
+    ("sweep_surplus", ProtocolRole::Treasury),
+    ("cover_bad_debt", ProtocolRole::Treasury),
+];
+
+fn fresh_protocol(authority: Pubkey) -> Protocol {
+    let mut protocol = Protocol {
+        authority: Pubkey::default(),
+        bump: 0,
+        pusd_mint: Pubkey::default(),
+        global_debt_ceiling: 0,
+        total_debt_pusd: 0,
+        accrued_surplus_pusd: 0,
+        realized_fees_pusd: 0,
+        bad_debt_pusd: 0,
+        paused_actions: 0,
+        governance: Pubkey::default(),
+        guardian: Pubkey::default(),
+        risk_admin: Pubkey::default(),
+        treasury_authority: Pubkey::default(),
+        pending_authority: Pubkey::default(),
+        seed_key: Pubkey::default(),
+        settlement_phase: SettlementPhase::Live,
+        _reserved: [0; 6],
+    };
+    init_protocol_state(&mut protocol, authority, Pubkey::new_unique(), 255, 1_000_000_000_000);
+    protocol
+}
+
+/// Protocol with each role handed to its own key; returns the holders in `ROLES` order
+fn delegated_protocol() -> (Protocol, [Pubkey; 3]) {
+    let mut protocol = fresh_protocol(Pubkey::new_unique());
+    let holders = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    for (role, holder) in ROLES.iter().zip(holders) {
+        protocol.set_role_holder(*role, holder);
+    }
+    (protocol, holders)
+}
+
+#[test]
+fn test_all_roles_start_with_authority() {
+    let authority = Pubkey::new_unique();
+    let protocol = fresh_protocol(authority);
+    for role in ROLES {
+        assert_eq!(protocol.role_holder(role), authority);
+    }
+}
+
+#[test]
+fn test_set_role_replaces_only_that_role() {
+    let authority = Pubkey::new_unique();
+    let mut protocol = fresh_protocol(authority);
+    let guardian = Pubkey::new_unique();
+
+    // Returns the previous holder for the RoleUpdated event
+    assert_eq!(protocol.set_role_holder(ProtocolRole::Guardian, guardian), authority);
+    assert_eq!(protocol.guardian, guardian);
+    assert_eq!(protocol.role_holder(ProtocolRole::RiskAdmin), authority);
+    assert_eq!(protocol.role_holder(ProtocolRole::Treasury), authority);
+
+    // The root admin is not a role slot and stays put
+    assert_eq!(protocol.authority, authority);
+
+    // Reassigning again hands back the key it replaced
+    let next = Pubkey::new_unique();
+    assert_eq!(protocol.set_role_holder(ProtocolRole::Guardian, next), guardian);
+    assert_eq!(protocol.role_holder(ProtocolRole::Guardian), next);
+}
+
+#[test]
+fn test_each_role_maps_to_its_own_field() {
+    let (protocol, [guardian, risk_admin, treasury]) = delegated_protocol();
+    assert_eq!(protocol.guardian, guardian);
+    assert_eq!(protocol.risk_admin, risk_admin);
+    assert_eq!(protocol.treasury_authority, treasury);
+}
+
+#[test]
+fn test_roles_rejected_on_other_roles_handlers() {
+    let (protocol, holders) = delegated_protocol();
+
+    for (handler, required) in GATED_HANDLERS {
+        for (role, holder) in ROLES.iter().zip(holders) {
+            let accepted = protocol.role_holder(required) == holder;
+            assert_eq!(
+                accepted,
+                *role == required,
+                "{:?} on {} should be {}",
+                role,
+                handler,
+                if *role == required { "accepted" } else { "rejected" }
+            );
+        }
+        // Delegating a role never lets the root admin through by accident
+        assert_ne!(protocol.role_holder(required), protocol.authority, "authority on {}", handler);
+    }
+}
+
+#[test]
+fn test_role_holders_cannot_set_roles() {
+    // `set_role` is gated on the root admin alone
+    let (protocol, holders) = delegated_protocol();
+    for holder in holders {
+        assert_ne!(holder, protocol.authority);
+    }
+}
@@ -0,0 +1,43 @@
+use pusd::state::{
+    accrue_savings_index, savings_pusd_for_shares, savings_shares_for_pusd, RATE_INDEX_ONE,
+    SECONDS_PER_YEAR,
+};
+
+/// sPUSD savings vault math tests
+
+const ONE_YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+#[test]
+fn test_savings_index_grows_within_funded_surplus() {
+    // 10% savings rate on 1,000 sPUSD for a year, fully funded: 100 PUSD of interest
+    let (index, interest) =
+        accrue_savings_index(RATE_INDEX_ONE, 1_000, ONE_YEAR, 1_000_000_000, u64::MAX).unwrap();
+    assert_eq!(index, RATE_INDEX_ONE * 11 / 10);
+    assert_eq!(interest, 100_000_000);
+
+    // Only 50 PUSD of surplus available: the index grows by what is funded
+    let (index, interest) =
+        accrue_savings_index(RATE_INDEX_ONE, 1_000, ONE_YEAR, 1_000_000_000, 50_000_000).unwrap();
+    assert_eq!(index, RATE_INDEX_ONE * 105 / 100);
+    assert_eq!(interest, 50_000_000);
+
+    // An empty vault owes nothing
+    assert_eq!(
+        accrue_savings_index(RATE_INDEX_ONE, 1_000, ONE_YEAR, 0, u64::MAX),
+        Some((RATE_INDEX_ONE, 0))
+    );
+}
+
+#[test]
+fn test_savings_share_conversion_rounds_toward_vault() {
+    let index = RATE_INDEX_ONE * 11 / 10;
+
+    // 1 PUSD buys 0.909090 sPUSD at 1.1, which is worth slightly less than 1 PUSD
+    let shares = savings_shares_for_pusd(1_000_000, index).unwrap();
+    assert_eq!(shares, 909_090);
+    assert_eq!(savings_pusd_for_shares(shares, index), Some(999_999));
+
+    // At 1.0 the conversion is one-to-one
+    assert_eq!(savings_shares_for_pusd(1_000_000, RATE_INDEX_ONE), Some(1_000_000));
+    assert_eq!(savings_pusd_for_shares(1_000_000, RATE_INDEX_ONE), Some(1_000_000));
+}
@@ -0,0 +1,142 @@
+use anchor_lang::AccountDeserialize;
+use pusd::accounts::{Auction, CollateralConfig, Position, Protocol};
+use pusd::instructions::{
+    increase_position_debt, settle_auction_at_final_price, settle_position_at_final_price,
+};
+use pusd::state::{
+    settlement_cash_amount, settlement_collateral_owed, settlement_pusd_supply, SettlementPhase,
+    RATE_INDEX_ONE, REDEMPTION_BUCKET_NONE,
+};
+
+/// Global settlement tests
+
+fn zeroed<T: AccountDeserialize>(len: usize) -> T {
+    let data = vec![0u8; len];
+    T::try_deserialize_unchecked(&mut &data[..]).unwrap()
+}
+
+fn indebted_position(
+    protocol: &mut Protocol,
+    cfg: &mut CollateralConfig,
+    collateral_amount: u64,
+    debt: u64,
+) -> Position {
+    let mut pos: Position = zeroed(Position::LEN);
+    pos.collateral_amount = collateral_amount;
+    pos.redemption_bucket = REDEMPTION_BUCKET_NONE;
+    increase_position_debt(&mut pos, cfg, debt).unwrap();
+    cfg.total_debt_pusd += debt;
+    cfg.total_collateral += collateral_amount;
+    protocol.total_debt_pusd += debt;
+    pos
+}
+
+#[test]
+fn test_position_settles_at_frozen_price() {
+    // 1,000 PUSD against 10 tokens (9 decimals) frozen at $150: owes 6.666..67 tokens
+    let (owed, shortfall) =
+        settlement_collateral_owed(1_000_000_000, 10_000_000_000, 9, 150_000_000).unwrap();
+    assert_eq!(owed, 6_666_666_667);
+    assert_eq!(shortfall, 0);
+
+    // Underwater at $50: all 10 tokens go to holders, $500 of debt is uncovered
+    let (owed, shortfall) =
+        settlement_collateral_owed(1_000_000_000, 10_000_000_000, 9, 50_000_000).unwrap();
+    assert_eq!(owed, 10_000_000_000);
+    assert_eq!(shortfall, 500_000_000);
+
+    // No frozen price, no settlement
+    assert_eq!(settlement_collateral_owed(1_000_000_000, 10_000_000_000, 9, 0), None);
+}
+
+#[test]
+fn test_cash_is_pro_rata_and_never_overpays() {
+    // 3 tokens set aside against 1,000 PUSD outstanding
+    let set_aside = 3_000_000_000u64;
+    let supply = 1_000_000_000u64;
+    assert_eq!(settlement_cash_amount(250_000_000, set_aside, supply), Some(750_000_000));
+
+    // Cashing in pieces rounds down, so the sum stays within what was set aside
+    let pieces = [333_333_333u64, 333_333_333, 333_333_334];
+    let paid: u64 = pieces
+        .iter()
+        .map(|p| settlement_cash_amount(*p, set_aside, supply).unwrap())
+        .sum();
+    assert!(paid <= set_aside);
+
+    assert_eq!(settlement_cash_amount(1, set_aside, 0), None);
+}
+
+#[test]
+fn test_cage_settle_thaw_pack_cash() {
+    let mut protocol: Protocol = zeroed(Protocol::LEN);
+    let mut cfg: CollateralConfig = zeroed(CollateralConfig::LEN);
+    cfg.rate_index = RATE_INDEX_ONE;
+
+    // 1,000 PUSD against 10 tokens, 400 PUSD against 2 tokens, and an auction of 3 tokens
+    // still owed 300 PUSD when the protocol is caged
+    let mut healthy = indebted_position(&mut protocol, &mut cfg, 10_000_000_000, 1_000_000_000);
+    let mut underwater = indebted_position(&mut protocol, &mut cfg, 2_000_000_000, 400_000_000);
+    let mut auctioned: Position = zeroed(Position::LEN);
+    let mut auction: Auction = zeroed(Auction::LEN);
+    auction.collateral_amount = 3_000_000_000;
+    auction.debt_remaining_pusd = 300_000_000;
+    auction.active = true;
+    cfg.active_auctions = 1;
+    cfg.total_debt_pusd += 300_000_000;
+    cfg.total_collateral += 3_000_000_000;
+    protocol.total_debt_pusd += 300_000_000;
+
+    // Cage and freeze the price at $150
+    protocol.settlement_phase = SettlementPhase::Caged;
+    cfg.final_price_6dp = 150_000_000;
+    assert!(!cfg.settlement_cashable());
+
+    let (owed, shortfall) =
+        settle_position_at_final_price(&mut protocol, &mut cfg, &mut healthy, 9).unwrap();
+    assert_eq!((owed, shortfall), (6_666_666_667, 0));
+    assert_eq!(healthy.collateral_amount, 3_333_333_333);
+    assert_eq!(healthy.normalized_debt, 0);
+
+    let (owed, shortfall) =
+        settle_position_at_final_price(&mut protocol, &mut cfg, &mut underwater, 9).unwrap();
+    assert_eq!((owed, shortfall), (2_000_000_000, 100_000_000));
+
+    // Positions are settled, but the auction can still add to the pot
+    assert_eq!(cfg.total_normalized_debt, 0);
+    assert!(!cfg.settlement_cashable());
+
+    let (owed, shortfall, returned) = settle_auction_at_final_price(
+        &mut protocol,
+        &mut cfg,
+        &mut auction,
+        &mut auctioned,
+        9,
+    )
+    .unwrap();
+    assert_eq!((owed, shortfall, returned), (2_000_000_000, 0, 1_000_000_000));
+    assert_eq!(auctioned.collateral_amount, 1_000_000_000);
+    assert!(!auction.active);
+    assert!(cfg.settlement_cashable());
+
+    // Every debt left the books, and only the uncovered part is a shortfall
+    assert_eq!(cfg.total_debt_pusd, 0);
+    assert_eq!(protocol.total_debt_pusd, 0);
+    assert_eq!(cfg.settlement_collateral, 10_666_666_667);
+    assert_eq!(cfg.settlement_shortfall_pusd, 100_000_000);
+
+    // Thaw: 1,700 PUSD were minted plus 50 PUSD of surplus held by the treasury, which is
+    // burned rather than diluting holders
+    let supply = settlement_pusd_supply(1_750_000_000, 50_000_000).unwrap();
+    assert_eq!(supply, 1_700_000_000);
+    assert_eq!(settlement_pusd_supply(50_000_000, 50_000_001), None);
+
+    // Every holder packs and cashes; the pot is shared out without overpaying
+    let packed = [1_000_000_000u64, 500_000_000, 200_000_000];
+    let paid: u64 = packed
+        .iter()
+        .map(|p| settlement_cash_amount(*p, cfg.settlement_collateral, supply).unwrap())
+        .sum();
+    assert!(paid <= cfg.settlement_collateral);
+    assert!(cfg.settlement_collateral - paid < packed.len() as u64);
+}
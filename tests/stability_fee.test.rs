@@ -0,0 +1,44 @@
+use pusd::state::{
+    accrue_rate_index, debt_to_normalized, normalized_to_debt, RATE_INDEX_ONE, SECONDS_PER_YEAR,
+};
+
+/// Stability fee math tests
+/// Exercise the rate index helpers directly; no validator is needed.
+
+const ONE_YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+#[test]
+fn test_rate_index_accrues_annual_fee() {
+    // 5% APR over a full year in one step
+    let index = accrue_rate_index(RATE_INDEX_ONE, 500, ONE_YEAR).unwrap();
+    assert_eq!(index, RATE_INDEX_ONE + RATE_INDEX_ONE / 20);
+
+    // No time elapsed or zero fee leaves the index untouched
+    assert_eq!(accrue_rate_index(index, 500, 0).unwrap(), index);
+    assert_eq!(accrue_rate_index(index, 0, ONE_YEAR).unwrap(), index);
+
+    // Accruing in steps compounds, so it ends slightly above the single-step result
+    let mut stepped = RATE_INDEX_ONE;
+    for _ in 0..12 {
+        stepped = accrue_rate_index(stepped, 500, ONE_YEAR / 12).unwrap();
+    }
+    assert!(stepped > index);
+}
+
+#[test]
+fn test_normalized_debt_round_trip() {
+    let index = accrue_rate_index(RATE_INDEX_ONE, 500, ONE_YEAR).unwrap();
+
+    // 1,000 PUSD minted at index 1.0 owes 1,050 PUSD after a year at 5%
+    let normalized = debt_to_normalized(1_000_000_000, RATE_INDEX_ONE, true).unwrap();
+    assert_eq!(normalized_to_debt(normalized, index), Some(1_050_000_000));
+
+    // Minting rounds the normalized amount up and debt rounds up, so a borrower never owes
+    // less than they minted
+    let minted = debt_to_normalized(1, index, true).unwrap();
+    assert!(normalized_to_debt(minted, index).unwrap() >= 1);
+
+    // Repaying rounds the normalized amount down, never removing more than was repaid
+    let repaid = debt_to_normalized(1, index, false).unwrap();
+    assert!(normalized_to_debt(repaid, index).unwrap() <= 1);
+}
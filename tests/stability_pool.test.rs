@@ -0,0 +1,69 @@
+use pusd::state::{StabilityPoolAccumulators, SP_PRECISION};
+
+/// Stability pool accumulator tests
+/// Exercise the product-sum math directly; no validator is needed.
+
+fn fresh_pool() -> StabilityPoolAccumulators {
+    StabilityPoolAccumulators {
+        product_p: SP_PRECISION,
+        sum_s: 0,
+        sum_s_previous: 0,
+        scale: 0,
+    }
+}
+
+#[test]
+fn test_offset_splits_losses_and_gains_pro_rata() {
+    let mut pool = fresh_pool();
+    let start = pool;
+
+    // A deposits 600 PUSD, B deposits 400 PUSD; both snapshot the fresh pool
+    let (a, b) = (600_000_000u64, 400_000_000u64);
+
+    // Pool absorbs 500 PUSD of debt for 5 collateral tokens (9dp)
+    pool.offset(a + b, 500_000_000, 5_000_000_000).unwrap();
+
+    assert_eq!(pool.compounded_deposit(a, start.product_p, start.scale), Some(300_000_000));
+    assert_eq!(pool.compounded_deposit(b, start.product_p, start.scale), Some(200_000_000));
+    assert_eq!(
+        pool.collateral_gain(a, start.product_p, start.sum_s, start.scale),
+        Some(3_000_000_000)
+    );
+    assert_eq!(
+        pool.collateral_gain(b, start.product_p, start.sum_s, start.scale),
+        Some(2_000_000_000)
+    );
+
+    // C joins after the first offset and only shares in the second
+    let joined = pool;
+    let c = 500_000_000u64;
+    pool.offset(300_000_000 + 200_000_000 + c, 100_000_000, 1_000_000_000).unwrap();
+    assert_eq!(pool.compounded_deposit(c, joined.product_p, joined.scale), Some(450_000_000));
+    assert_eq!(
+        pool.collateral_gain(c, joined.product_p, joined.sum_s, joined.scale),
+        Some(500_000_000)
+    );
+}
+
+#[test]
+fn test_offset_rescales_product_near_depletion() {
+    let mut pool = fresh_pool();
+    let start = pool;
+    let deposit = 10_000_000_000u64;
+
+    // Absorb all but one unit: P would fall below the scale threshold
+    pool.offset(deposit, deposit - 1, 7_000_000_000).unwrap();
+    assert_eq!(pool.scale, 1);
+    assert!(pool.product_p >= SP_PRECISION / 1_000_000_000);
+
+    // Deposit and gain still resolve across the scale change
+    assert_eq!(pool.compounded_deposit(deposit, start.product_p, start.scale), Some(1));
+    assert_eq!(
+        pool.collateral_gain(deposit, start.product_p, start.sum_s, start.scale),
+        Some(7_000_000_000)
+    );
+
+    // Absorbing the entire pool is rejected; callers leave a minimum behind
+    let mut pool = fresh_pool();
+    assert_eq!(pool.offset(deposit, deposit, 1), None);
+}
@@ -0,0 +1,40 @@
+use pusd::state::{pusd_metadata_valid, MAX_PUSD_NAME_LEN, MAX_PUSD_SYMBOL_LEN, MAX_PUSD_URI_LEN};
+
+/// Token-2022 PUSD mint tests
+/// Metadata accepted by `initialize_token_2022`.
+
+#[test]
+fn test_accepts_typical_metadata() {
+    assert!(pusd_metadata_valid("PumpUSD", "PUSD", "https://pumpusd.example/metadata.json"));
+    // URI is optional
+    assert!(pusd_metadata_valid("PumpUSD", "PUSD", ""));
+}
+
+#[test]
+fn test_name_and_symbol_are_required() {
+    assert!(!pusd_metadata_valid("", "PUSD", ""));
+    assert!(!pusd_metadata_valid("PumpUSD", "", ""));
+}
+
+#[test]
+fn test_length_limits_are_inclusive() {
+    let name = "n".repeat(MAX_PUSD_NAME_LEN);
+    let symbol = "s".repeat(MAX_PUSD_SYMBOL_LEN);
+    let uri = "u".repeat(MAX_PUSD_URI_LEN);
+    assert!(pusd_metadata_valid(&name, &symbol, &uri));
+
+    let long_name = "n".repeat(MAX_PUSD_NAME_LEN + 1);
+    let long_symbol = "s".repeat(MAX_PUSD_SYMBOL_LEN + 1);
+    let long_uri = "u".repeat(MAX_PUSD_URI_LEN + 1);
+    assert!(!pusd_metadata_valid(&long_name, &symbol, &uri));
+    assert!(!pusd_metadata_valid(&name, &long_symbol, &uri));
+    assert!(!pusd_metadata_valid(&name, &symbol, &long_uri));
+}
+
+#[test]
+fn test_limits_count_bytes_not_chars() {
+    // Multi-byte characters take more of the on-chain allocation than they look
+    let name = "€".repeat(MAX_PUSD_NAME_LEN / 3 + 1);
+    assert!(name.chars().count() <= MAX_PUSD_NAME_LEN);
+    assert!(!pusd_metadata_valid(&name, "PUSD", ""));
+}
@@ -0,0 +1,31 @@
+use pusd::state::{realize_accrued_surplus, sweepable_surplus};
+
+/// Treasury tests
+/// Fee collection and surplus sweeping math, exercised without a validator.
+
+#[test]
+fn test_collect_fees_realizes_accrued_surplus() {
+    // 120 PUSD of accrued stability fees on top of 30 PUSD already realized
+    let (minted, realized) = realize_accrued_surplus(120_000_000, 30_000_000).unwrap();
+    assert_eq!(minted, 120_000_000);
+    assert_eq!(realized, 150_000_000);
+
+    // Nothing accrued: nothing minted and realized fees unchanged
+    assert_eq!(realize_accrued_surplus(0, 30_000_000), Some((0, 30_000_000)));
+
+    // Realized fees never wrap
+    assert_eq!(realize_accrued_surplus(1, u64::MAX), None);
+}
+
+#[test]
+fn test_sweep_keeps_surplus_buffer() {
+    // 1,000 PUSD in the treasury with a 250 PUSD buffer: 750 may be swept
+    assert_eq!(sweepable_surplus(1_000_000_000, 250_000_000), 750_000_000);
+
+    // Exactly at the buffer, or below it (after covering bad debt), nothing may be swept
+    assert_eq!(sweepable_surplus(250_000_000, 250_000_000), 0);
+    assert_eq!(sweepable_surplus(100_000_000, 250_000_000), 0);
+
+    // No buffer configured: the whole balance is sweepable
+    assert_eq!(sweepable_surplus(42, 0), 42);
+}
@@ -0,0 +1,76 @@
+use pusd::errors::ErrorCode;
+use pusd::instructions::check_withdrawal_ltv;
+
+/// Collateral withdrawal tests
+/// LTV gating applied by `withdraw_collateral` to positions that still carry debt.
+
+const SOL_DECIMALS: u8 = 9;
+const ONE_SOL: u64 = 1_000_000_000;
+const INITIAL_LTV_BPS: u16 = 6_600;
+const MAINTENANCE_LTV_BPS: u16 = 6_000;
+
+/// 10 SOL position at `price_6dp`, withdrawing `withdraw` of it
+fn check(withdraw: u64, debt_pusd_6dp: u64, price_6dp: u128) -> anchor_lang::Result<()> {
+    let collateral = 10 * ONE_SOL;
+    check_withdrawal_ltv(
+        collateral,
+        collateral - withdraw,
+        debt_pusd_6dp,
+        SOL_DECIMALS,
+        price_6dp,
+        INITIAL_LTV_BPS,
+        MAINTENANCE_LTV_BPS,
+    )
+}
+
+#[test]
+fn test_withdraw_within_initial_ltv() {
+    // $1,000 of SOL backing $500: $800 left still supports 66%
+    assert!(check(2 * ONE_SOL, 500_000_000, 100_000_000).is_ok());
+}
+
+#[test]
+fn test_withdraw_past_initial_ltv_is_rejected() {
+    // $700 left would put $500 of debt at 71% LTV
+    assert_eq!(
+        check(3 * ONE_SOL, 500_000_000, 100_000_000).unwrap_err(),
+        ErrorCode::LtvExceeded.into()
+    );
+}
+
+#[test]
+fn test_initial_ltv_boundary_is_inclusive() {
+    // $660 against $1,000 remaining is exactly 66%
+    assert!(check(0, 660_000_000, 100_000_000).is_ok());
+    assert_eq!(
+        check(0, 660_000_001, 100_000_000).unwrap_err(),
+        ErrorCode::LtvExceeded.into()
+    );
+}
+
+#[test]
+fn test_unhealthy_position_cannot_withdraw() {
+    // Health 50% is below maintenance: even a dust withdrawal is refused
+    assert_eq!(
+        check(1, 2_000_000_000, 100_000_000).unwrap_err(),
+        ErrorCode::HealthBelowMaintenance.into()
+    );
+}
+
+#[test]
+fn test_price_drop_tightens_withdrawals() {
+    // The same withdrawal that passes at $100 fails once SOL trades at $70
+    assert!(check(ONE_SOL / 10, 500_000_000, 100_000_000).is_ok());
+    assert_eq!(
+        check(ONE_SOL / 10, 500_000_000, 70_000_000).unwrap_err(),
+        ErrorCode::LtvExceeded.into()
+    );
+}
+
+#[test]
+fn test_zero_price_blocks_withdrawal() {
+    assert_eq!(
+        check(1, 500_000_000, 0).unwrap_err(),
+        ErrorCode::HealthBelowMaintenance.into()
+    );
+}